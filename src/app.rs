@@ -1,25 +1,64 @@
 use anyhow::Result;
+use anyhow::anyhow;
 use crossterm::event::Event;
 use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
 use crossterm::event::KeyModifiers;
+use crossterm::event::KeyboardEnhancementFlags;
+use crossterm::event::PopKeyboardEnhancementFlags;
+use crossterm::event::PushKeyboardEnhancementFlags;
 use crossterm::event::{self};
 use crossterm::execute;
 use crossterm::terminal::EnterAlternateScreen;
 use crossterm::terminal::LeaveAlternateScreen;
 use crossterm::terminal::disable_raw_mode;
 use crossterm::terminal::enable_raw_mode;
+use crossterm::terminal::supports_keyboard_enhancement;
 use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
+use serde::Deserialize;
+use serde::Serialize;
 use std::io;
+use std::io::Stdout;
+use std::process::Stdio;
 use std::time::Duration;
+use tracing::warn;
 
+use crate::bookmarks::BookmarkAction;
+use crate::bookmarks::Bookmarks;
+use crate::bookmarks::BookmarksView;
+use crate::bookmarks::RESERVED_MARKS;
 use crate::buck::BuckProject;
+use crate::cells::CellBrowser;
+use crate::changes::ChangedTargetsView;
+use crate::dep_path::DepPathView;
+use crate::dep_tree::DepTree;
+use crate::editor::Editor;
+use crate::editor::EditorRequest;
 use crate::events::EventHandler;
+use crate::export::ExportDialog;
+use crate::goto::GotoPrompt;
+use crate::graph_view::GraphView;
+use crate::jump_list::JumpListView;
+use crate::jump_list::Location;
+use crate::owner::OwnerLookup;
+use crate::platform::PlatformChoice;
+use crate::platform::PlatformPicker;
+use crate::query_console::QueryConsole;
+use crate::rdeps::RdepsView;
+use crate::recent::ActionResult;
+use crate::recent::RecentAction;
+use crate::recent::RecentView;
 use crate::scheduler::Scheduler;
-use crate::ui::UI;
+use crate::session::Session;
+use crate::tabs::Tab;
+use crate::tabs::Tabs;
+use crate::ui::DetailsTab;
 use crate::ui::Pane;
+use crate::ui::PaneGroup;
+use crate::ui::UI;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum SearchPane {
     CurrentDirectory,
     Targets,
@@ -90,6 +129,26 @@ pub struct App {
     event_handler: EventHandler,
     scheduler: Scheduler,
     pub search_state: SearchState,
+    query_console: QueryConsole,
+    rdeps_view: RdepsView,
+    dep_tree: DepTree,
+    dep_path_view: DepPathView,
+    export_dialog: ExportDialog,
+    graph_view: GraphView,
+    platform_picker: PlatformPicker,
+    owner_lookup: OwnerLookup,
+    changed_targets: ChangedTargetsView,
+    cell_browser: CellBrowser,
+    goto_prompt: GotoPrompt,
+    jump_list_view: JumpListView,
+    bookmarks: Bookmarks,
+    bookmarks_view: BookmarksView,
+    // `m` was pressed, the next key names the mark
+    pending_mark: bool,
+    recent_view: RecentView,
+    tabs: Tabs,
+    // The terminal reports Ctrl+I apart from Tab, see `run`
+    keyboard_enhanced: bool,
     should_quit: bool,
     show_actions: bool,
     selected_action: usize,
@@ -102,6 +161,22 @@ impl App {
         let event_handler = EventHandler::new();
         let scheduler = Scheduler::new();
         let search_state = SearchState::new();
+        let query_console = QueryConsole::new();
+        let rdeps_view = RdepsView::new();
+        let dep_tree = DepTree::new();
+        let dep_path_view = DepPathView::new();
+        let export_dialog = ExportDialog::new();
+        let graph_view = GraphView::new();
+        let platform_picker = PlatformPicker::new();
+        let owner_lookup = OwnerLookup::new();
+        let changed_targets = ChangedTargetsView::new();
+        let cell_browser = CellBrowser::new();
+        let goto_prompt = GotoPrompt::new();
+        let jump_list_view = JumpListView::new();
+        let bookmarks = Bookmarks::load(&project.repo_root);
+        let bookmarks_view = BookmarksView::new();
+        let recent_view = RecentView::new();
+        let tabs = Tabs::new();
 
         Ok(Self {
             project,
@@ -109,6 +184,24 @@ impl App {
             event_handler,
             scheduler,
             search_state,
+            query_console,
+            rdeps_view,
+            dep_tree,
+            dep_path_view,
+            export_dialog,
+            graph_view,
+            platform_picker,
+            owner_lookup,
+            changed_targets,
+            cell_browser,
+            goto_prompt,
+            jump_list_view,
+            bookmarks,
+            bookmarks_view,
+            pending_mark: false,
+            recent_view,
+            tabs,
+            keyboard_enhanced: false,
             should_quit: false,
             show_actions: false,
             selected_action: 0,
@@ -119,7 +212,41 @@ impl App {
         &self.scheduler
     }
 
+    /// Pick up where the last session in this project left off, when that was inside the
+    /// path buck-tui was started at
+    pub fn restore_session(&mut self) {
+        let Some(session) = Session::load(&self.project.repo_root) else {
+            return;
+        };
+        let location = session.location;
+        if !location.current_path.starts_with(&self.project.root_path)
+            || !location.selected_directory.is_dir()
+        {
+            return;
+        }
+
+        self.project.restore_location(location, &self.scheduler);
+        self.ui.current_pane = session.pane;
+        self.ui.current_group = session.group;
+        self.search_state.query = session.search_query;
+        self.search_state.searching_in_pane = session.search_pane;
+    }
+
+    fn save_session(&self) {
+        let session = Session {
+            location: self.project.location(),
+            pane: self.ui.current_pane,
+            group: self.ui.current_group,
+            search_query: self.search_state.query.clone(),
+            search_pane: self.search_state.searching_in_pane.clone(),
+        };
+        if let Err(e) = session.save(&self.project.repo_root) {
+            warn!("Failed to save the session: {}", e);
+        }
+    }
+
     pub async fn initialize(&mut self) {
+        self.project.request_build_file_names(&self.scheduler);
         self.project
             .update_targets_for_selected_directory(&self.scheduler);
     }
@@ -128,6 +255,14 @@ impl App {
         enable_raw_mode()?;
         let mut stdout = io::stdout();
         execute!(stdout, EnterAlternateScreen)?;
+        // Ctrl+I (jump forward) and Tab are the same key unless the terminal disambiguates them
+        self.keyboard_enhanced = supports_keyboard_enhancement().unwrap_or(false);
+        if self.keyboard_enhanced {
+            execute!(
+                stdout,
+                PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+            )?;
+        }
         let backend = CrosstermBackend::new(stdout);
         let mut terminal = Terminal::new(backend)?;
 
@@ -136,21 +271,150 @@ impl App {
             self.project
                 .update_loaded_target_results(&self.scheduler)
                 .await;
+            self.project
+                .update_build_file_results(&self.scheduler)
+                .await;
+            self.project.update_configured_target_results().await;
+            self.project.request_configured_target(&self.scheduler);
+            if self.ui.details_tab == DetailsTab::Sources {
+                self.ui
+                    .sources
+                    .update(self.project.get_selected_target(), &self.project.cells);
+            }
+            self.query_console.update_results().await;
+            self.rdeps_view.update_results().await;
+            self.dep_tree
+                .update_results(&self.project, &self.scheduler)
+                .await;
+            self.dep_path_view.update_results().await;
+            self.graph_view.update_results().await;
+            self.platform_picker.update_results().await;
+            self.owner_lookup.update_results().await;
+
+            let editor_requests = std::mem::take(&mut *self.project.editor_requests.lock().await);
+            for request in editor_requests {
+                if let Err(e) = Self::launch_editor(&mut terminal, &request, self.keyboard_enhanced)
+                {
+                    warn!("Failed to open {}: {}", request.file.display(), e);
+                    self.ui
+                        .show_error(format!("Cannot open {}: {}", request.file.display(), e));
+                } else if let Some(label) = &request.label {
+                    self.project
+                        .recent
+                        .record(label, RecentAction::Opened, ActionResult::Done);
+                }
+            }
+            for (labels, action, result) in self.project.recent.update_results().await {
+                let what = match labels.as_slice() {
+                    [label] => label.clone(),
+                    _ => format!("{} targets", labels.len()),
+                };
+                let command = action.command().unwrap_or_default();
+                match result {
+                    ActionResult::Failed(reason) => self
+                        .ui
+                        .show_error(format!("buck2 {} {} failed: {}", command, what, reason)),
+                    _ => self
+                        .ui
+                        .show_info(format!("buck2 {} {} succeeded", command, what)),
+                }
+            }
+            if let Some(error) = self.project.navigation_error.take() {
+                self.ui.show_error(error);
+            }
+            match self.export_dialog.update_results().await {
+                Some(Ok(message)) => self.ui.show_info(message),
+                Some(Err(e)) => self.ui.show_error(format!("Export failed: {}", e)),
+                None => {}
+            }
+            self.changed_targets
+                .update_results(&mut self.project, &self.scheduler)
+                .await;
 
             terminal.draw(|f| {
-                self.ui.draw(f, &self.project, &self.search_state);
+                self.ui
+                    .draw(f, &self.project, &self.search_state, &self.tabs);
 
                 if self.show_actions {
-                    self.ui.draw_actions_popup(f, self.selected_action);
+                    let target_count = self.project.action_labels().len();
+                    self.ui
+                        .draw_actions_popup(f, self.selected_action, target_count);
+                }
+
+                if self.query_console.active {
+                    self.ui.draw_query_console(f, &self.query_console);
+                }
+
+                if self.rdeps_view.active {
+                    self.ui.draw_rdeps_view(f, &self.rdeps_view);
+                }
+
+                if self.dep_tree.active {
+                    self.ui.draw_dep_tree(f, &self.dep_tree);
+                }
+
+                if self.dep_path_view.active {
+                    self.ui.draw_dep_path_view(f, &self.dep_path_view);
+                }
+
+                if self.graph_view.active {
+                    self.ui.draw_graph_view(f, &self.graph_view);
+                }
+
+                if self.owner_lookup.active {
+                    self.ui.draw_owner_lookup(f, &self.owner_lookup);
+                }
+
+                if self.changed_targets.active {
+                    self.ui
+                        .draw_changed_targets(f, &self.changed_targets, &self.project.recent);
+                }
+
+                if self.platform_picker.active {
+                    self.ui.draw_platform_picker(f, &self.platform_picker);
+                }
+
+                if self.cell_browser.active {
+                    self.ui.draw_cell_browser(f, &self.cell_browser);
+                }
+
+                if self.goto_prompt.active {
+                    self.ui.draw_goto_prompt(f, &self.goto_prompt);
+                }
+
+                if self.recent_view.active {
+                    self.ui
+                        .draw_recent(f, &self.recent_view, &self.project.recent);
+                }
+
+                if self.bookmarks_view.active {
+                    self.ui
+                        .draw_bookmarks(f, &self.bookmarks_view, &self.bookmarks, &self.project);
+                }
+
+                if self.jump_list_view.active {
+                    self.ui
+                        .draw_jump_list(f, &self.jump_list_view, &self.project);
+                }
+
+                if self.export_dialog.active {
+                    self.ui.draw_export_dialog(f, &self.export_dialog);
                 }
             })?;
 
             if event::poll(Duration::from_millis(100))? {
                 let event = event::read()?;
+                let cell = self.current_cell();
                 self.handle_event(event).await?;
+                self.report_cell_change(cell);
             }
         }
 
+        self.save_session();
+
+        if self.keyboard_enhanced {
+            execute!(terminal.backend_mut(), PopKeyboardEnhancementFlags)?;
+        }
         disable_raw_mode()?;
         execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
         terminal.show_cursor()?;
@@ -158,7 +422,314 @@ impl App {
         Ok(())
     }
 
+    /// Open a file in the configured editor. Editors running in the terminal take it over
+    /// until they exit, GUI editors are left running on their own.
+    fn launch_editor(
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+        request: &EditorRequest,
+        keyboard_enhanced: bool,
+    ) -> Result<()> {
+        let editor = Editor::from_env();
+        let mut command = editor.command(&request.file, request.line);
+
+        if !editor.is_terminal() {
+            // Keep GUI editors off the terminal the TUI draws on, and reap them once they exit
+            let mut child = command
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()?;
+            std::thread::spawn(move || child.wait());
+            return Ok(());
+        }
+
+        if keyboard_enhanced {
+            execute!(terminal.backend_mut(), PopKeyboardEnhancementFlags)?;
+        }
+        disable_raw_mode()?;
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+
+        let status = command.status();
+
+        enable_raw_mode()?;
+        execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+        if keyboard_enhanced {
+            execute!(
+                terminal.backend_mut(),
+                PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+            )?;
+        }
+        terminal.clear()?;
+
+        let status = status?;
+        if !status.success() {
+            return Err(anyhow!("{} exited with {}", editor.program(), status));
+        }
+        Ok(())
+    }
+
+    fn current_cell(&self) -> Option<String> {
+        self.project
+            .cell_for_path(&self.project.current_path)
+            .map(|cell| cell.to_string())
+    }
+
+    /// Mark navigating across a cell boundary in the status bar
+    fn report_cell_change(&mut self, previous: Option<String>) {
+        let cell = self.current_cell();
+        if cell != previous
+            && let Some(cell) = cell
+            && self.ui.status_message.is_none()
+        {
+            self.ui.show_info(format!("Entered cell {}", cell));
+        }
+    }
+
+    /// Navigate the explorer to the package of `label` and focus its target
+    fn jump_to_label(&mut self, label: &str) -> Result<()> {
+        self.project.navigate_to_label(label, &self.scheduler)?;
+        self.ui.current_group = PaneGroup::Inspector;
+        self.ui.current_pane = Pane::Targets;
+        Ok(())
+    }
+
+    /// Mark the selected target when the inspector has focus, the selected directory otherwise
+    fn set_mark(&mut self, mark: char) {
+        if RESERVED_MARKS.contains(&mark) {
+            self.ui.show_error(format!(
+                "'{}' moves or deletes in the bookmarks popup, pick another mark",
+                mark
+            ));
+            return;
+        }
+
+        let mut location = self.project.location();
+        if self.ui.current_group != PaneGroup::Inspector {
+            location.target = None;
+        }
+        let place = location
+            .target
+            .clone()
+            .or_else(|| self.project.package_for_path(&location.selected_directory))
+            .unwrap_or_else(|| location.selected_directory.display().to_string());
+
+        match self.bookmarks.set(mark, location) {
+            Ok(()) => self.ui.show_info(format!("Marked '{}' at {}", mark, place)),
+            Err(e) => self
+                .ui
+                .show_error(format!("Cannot save mark '{}': {}", mark, e)),
+        }
+    }
+
+    /// Go to a bookmarked location, recorded in the jump list like any other jump
+    fn go_to_location(&mut self, location: Location) {
+        if !location.selected_directory.is_dir() {
+            self.ui.show_error(format!(
+                "{} no longer exists",
+                location.selected_directory.display()
+            ));
+            return;
+        }
+
+        let has_target = location.target.is_some();
+        self.project.record_jump();
+        self.project.restore_location(location, &self.scheduler);
+        if has_target {
+            self.ui.current_group = PaneGroup::Inspector;
+            self.ui.current_pane = Pane::Targets;
+        } else {
+            self.ui.current_group = PaneGroup::Explorer;
+            self.ui.current_pane = Pane::CurrentDirectory;
+        }
+    }
+
+    fn jump_back(&mut self) {
+        if !self.project.jump_back(&self.scheduler) {
+            self.ui.show_info("At the start of the jump list");
+        }
+    }
+
+    fn jump_forward(&mut self) {
+        if !self.project.jump_forward(&self.scheduler) {
+            self.ui.show_info("At the end of the jump list");
+        }
+    }
+
+    /// Take the view state of the shown tab out of the project, UI and search state
+    fn park_tab(&mut self) -> Tab {
+        Tab {
+            location: self.project.location(),
+            jump_list: std::mem::take(&mut self.project.jump_list),
+            target_filter: self.project.target_filter.take(),
+            target_selection: std::mem::take(&mut self.project.target_selection),
+            visual_anchor: self.project.visual_anchor.take(),
+            pane: self.ui.current_pane,
+            group: self.ui.current_group,
+            search_state: std::mem::replace(&mut self.search_state, SearchState::new()),
+        }
+    }
+
+    /// Show a parked tab. Its targets are usually still loaded, so this is instant.
+    fn show_tab(&mut self, tab: Tab) {
+        self.project.jump_list = tab.jump_list;
+        // Set before the location, the targets it lists are filtered
+        self.project.target_filter = tab.target_filter;
+        self.project.target_selection = tab.target_selection;
+        self.project.restore_location(tab.location, &self.scheduler);
+        self.project.visual_anchor = tab.visual_anchor;
+        self.ui.current_pane = tab.pane;
+        self.ui.current_group = tab.group;
+        self.search_state = tab.search_state;
+    }
+
+    /// Open a tab at the current location, right after the shown one
+    fn open_tab(&mut self) {
+        let current = self.park_tab();
+        self.tabs.open(current);
+        // The new tab starts without the filter of the parked one
+        self.project.set_target_filter(None);
+        self.ui.show_info(format!(
+            "Tab {} of {}",
+            self.tabs.active + 1,
+            self.tabs.count()
+        ));
+    }
+
+    fn switch_tab(&mut self, index: usize) {
+        if index == self.tabs.active || index >= self.tabs.count() {
+            return;
+        }
+        let current = self.park_tab();
+        let tab = self.tabs.switch(index, current);
+        self.show_tab(tab);
+    }
+
+    fn close_tab(&mut self) {
+        match self.tabs.close() {
+            Some(tab) => self.show_tab(tab),
+            None => self.ui.show_info("Cannot close the last tab"),
+        }
+    }
+
+    /// Route a key to the open overlay (query console or one of the dependency views).
+    /// Returns false when no overlay is open.
+    fn handle_overlay_key(&mut self, key: KeyEvent) -> bool {
+        let picked = if self.query_console.active {
+            self.query_console
+                .handle_key(key, &self.project, &self.scheduler)
+        } else if self.rdeps_view.active {
+            self.rdeps_view
+                .handle_key(key, &self.project, &self.scheduler)
+        } else if self.dep_tree.active {
+            self.dep_tree
+                .handle_key(key, &self.project, &self.scheduler)
+        } else if self.dep_path_view.active {
+            self.dep_path_view
+                .handle_key(key, &self.project, &self.scheduler)
+        } else if self.graph_view.active {
+            self.graph_view
+                .handle_key(key, &self.project, &self.scheduler)
+        } else if self.owner_lookup.active {
+            self.owner_lookup
+                .handle_key(key, &self.project, &self.scheduler)
+        } else if self.changed_targets.active {
+            self.changed_targets
+                .handle_key(key, &mut self.project, &self.scheduler)
+        } else if self.platform_picker.active {
+            match self.platform_picker.handle_key(key) {
+                Some(PlatformChoice::Platform(platform)) => {
+                    self.ui
+                        .show_info(format!("Configured graph for {}", platform));
+                    self.project.set_target_platform(Some(platform));
+                }
+                Some(PlatformChoice::Unconfigured) => {
+                    self.ui.show_info("Back to the unconfigured graph");
+                    self.project.set_target_platform(None);
+                }
+                None => {}
+            }
+            None
+        } else if self.goto_prompt.active {
+            self.goto_prompt.handle_key(key, &self.project)
+        } else if self.recent_view.active {
+            self.recent_view.handle_key(key, &self.project.recent)
+        } else if self.bookmarks_view.active {
+            match self.bookmarks_view.handle_key(key, &self.bookmarks) {
+                Some(BookmarkAction::Jump(location)) => self.go_to_location(location),
+                Some(BookmarkAction::Delete(index)) => {
+                    if let Err(e) = self.bookmarks.remove(index) {
+                        self.ui.show_error(format!("Cannot save bookmarks: {}", e));
+                    }
+                }
+                None => {}
+            }
+            None
+        } else if self.jump_list_view.active {
+            if let Some(index) = self.jump_list_view.handle_key(key, &self.project.jump_list) {
+                self.project.jump_to_entry(index, &self.scheduler);
+            }
+            None
+        } else if self.cell_browser.active {
+            if let Some((cell, root)) = self.cell_browser.handle_key(key) {
+                self.project.navigate_to_directory(root, &self.scheduler);
+                self.ui.current_group = PaneGroup::Explorer;
+                self.ui.current_pane = Pane::CurrentDirectory;
+                self.ui.show_info(format!("Cell {}", cell));
+            }
+            None
+        } else if self.export_dialog.active {
+            self.export_dialog
+                .handle_key(key, &self.project, &self.scheduler);
+            None
+        } else {
+            return false;
+        };
+
+        if let Some(label) = picked {
+            match self.jump_to_label(&label) {
+                Ok(()) => {
+                    self.query_console.close();
+                    self.rdeps_view.close();
+                    self.dep_tree.close();
+                    self.dep_path_view.close();
+                    self.export_dialog.close();
+                    self.graph_view.close();
+                    self.owner_lookup.close();
+                    self.changed_targets.close();
+                    self.goto_prompt.close();
+                    self.recent_view.close();
+                }
+                Err(e) => {
+                    warn!("Failed to jump to {}: {}", label, e);
+                    self.ui
+                        .show_error(format!("Cannot jump to {}: {}", label, e));
+                }
+            }
+        }
+        true
+    }
+
     async fn handle_event(&mut self, event: Event) -> Result<()> {
+        if let Event::Key(key) = event {
+            self.ui.status_message = None;
+
+            if !(key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL))
+                && self.handle_overlay_key(key)
+            {
+                return Ok(());
+            }
+
+            if std::mem::take(&mut self.pending_mark) {
+                if let KeyCode::Char(mark) = key.code {
+                    self.set_mark(mark);
+                }
+                return Ok(());
+            }
+        }
+
+        // Keys opening overlays are only taken when no search or actions popup is open
+        let normal_mode = !self.search_state.active && !self.show_actions;
+
         match event {
             Event::Key(key) => match key.code {
                 KeyCode::Char('q') => {
@@ -182,8 +753,11 @@ impl App {
                 }
                 KeyCode::Esc => {
                     // Esc handled by event handler (exits search or actions mode)
-                    // Only quit app if not in any mode
-                    if !self.search_state.active && !self.show_actions {
+                    // Only quit app if not in any mode and no targets are selected
+                    if normal_mode && self.project.has_selection() {
+                        self.project.clear_selection();
+                        self.ui.show_info("Selection cleared");
+                    } else if !self.search_state.active && !self.show_actions {
                         self.should_quit = true;
                     } else {
                         self.event_handler
@@ -202,6 +776,100 @@ impl App {
                 KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                     self.should_quit = true;
                 }
+                // Vim-style jumps: Ctrl+O goes back, Ctrl+I forward. H and L do the same in
+                // terminals where Ctrl+I can't be told apart from Tab.
+                KeyCode::Char('o')
+                    if normal_mode && key.modifiers.contains(KeyModifiers::CONTROL) =>
+                {
+                    self.jump_back();
+                }
+                KeyCode::Char('i')
+                    if normal_mode && key.modifiers.contains(KeyModifiers::CONTROL) =>
+                {
+                    self.jump_forward();
+                }
+                KeyCode::Char('J') if normal_mode => {
+                    self.jump_list_view.open(&self.project.jump_list);
+                }
+                KeyCode::Char('t') if normal_mode => {
+                    self.open_tab();
+                }
+                KeyCode::Char('x') if normal_mode => {
+                    self.close_tab();
+                }
+                KeyCode::Char('[') if normal_mode => {
+                    let count = self.tabs.count();
+                    self.switch_tab((self.tabs.active + count - 1) % count);
+                }
+                KeyCode::Char(']') if normal_mode => {
+                    self.switch_tab((self.tabs.active + 1) % self.tabs.count());
+                }
+                KeyCode::Char(digit @ '1'..='9') if normal_mode => {
+                    self.switch_tab(digit as usize - '1' as usize);
+                }
+                KeyCode::Char('R') if normal_mode => {
+                    self.recent_view.open();
+                }
+                KeyCode::Char('m') if normal_mode => {
+                    self.pending_mark = true;
+                    self.ui.show_info("Mark: press a key to name it");
+                }
+                KeyCode::Char('\'') if normal_mode => {
+                    self.bookmarks_view.open();
+                }
+                KeyCode::Char('H') if normal_mode => {
+                    self.jump_back();
+                }
+                KeyCode::Char('L') if normal_mode => {
+                    self.jump_forward();
+                }
+                KeyCode::Char(':') if normal_mode => {
+                    self.query_console.open();
+                }
+                KeyCode::Char('r') if normal_mode => {
+                    if let Some(target) = self.project.get_selected_target() {
+                        let label = target.full_target_label_name.clone();
+                        self.rdeps_view.open(label, &self.project, &self.scheduler);
+                    }
+                }
+                KeyCode::Char('D') if normal_mode => {
+                    if let Some(target) = self.project.get_selected_target() {
+                        let label = target.full_target_label_name.clone();
+                        self.dep_tree.open(label, &self.project, &self.scheduler);
+                    }
+                }
+                KeyCode::Char('w') if normal_mode => {
+                    if let Some(target) = self.project.get_selected_target() {
+                        let label = target.full_target_label_name.clone();
+                        self.dep_path_view.open(label, &self.project);
+                    }
+                }
+                KeyCode::Char('G') if normal_mode => {
+                    if let Some(target) = self.project.get_selected_target() {
+                        let label = target.full_target_label_name.clone();
+                        self.graph_view.open(label, &self.project, &self.scheduler);
+                    }
+                }
+                KeyCode::Char('O') if normal_mode => {
+                    self.owner_lookup.open(&self.project);
+                }
+                KeyCode::Char('c') if normal_mode => {
+                    self.changed_targets.open(&self.project, &self.scheduler);
+                }
+                KeyCode::Char('g') if normal_mode => {
+                    self.goto_prompt.open();
+                }
+                KeyCode::Char('C') if normal_mode => {
+                    self.cell_browser.open(&self.project);
+                }
+                KeyCode::Char('p') if normal_mode => {
+                    self.platform_picker.open(&self.project, &self.scheduler);
+                }
+                KeyCode::Char('e') if normal_mode => {
+                    if self.project.get_selected_buck_package_name().is_some() {
+                        self.export_dialog.open(&self.project);
+                    }
+                }
                 _ => {
                     self.event_handler
                         .handle_key_event(
@@ -0,0 +1,153 @@
+use serde_json::Map;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// A row of the "All attributes" tree in the Details pane
+#[derive(Debug, Clone)]
+pub struct AttributeRow {
+    // Slash separated path of keys and list indices, e.g. `srcs/0`
+    pub path: String,
+    pub depth: usize,
+    // Attribute name or dict key, None for list items
+    pub key: Option<String>,
+    pub value: String,
+    pub expandable: bool,
+    pub expanded: bool,
+}
+
+/// Flatten the attribute map into rows, descending only into expanded lists and dicts
+pub fn attribute_rows(
+    attributes: &Map<String, Value>,
+    expanded: &HashSet<String>,
+) -> Vec<AttributeRow> {
+    let mut rows = Vec::new();
+    for (name, value) in attributes {
+        push_rows(
+            &mut rows,
+            name.clone(),
+            Some(name.clone()),
+            value,
+            0,
+            expanded,
+        );
+    }
+    rows
+}
+
+fn push_rows(
+    rows: &mut Vec<AttributeRow>,
+    path: String,
+    key: Option<String>,
+    value: &Value,
+    depth: usize,
+    expanded: &HashSet<String>,
+) {
+    let (summary, expandable) = match value {
+        Value::Array(items) if items.is_empty() => ("[]".to_string(), false),
+        Value::Array(items) => (format!("[{} items]", items.len()), true),
+        Value::Object(map) if map.is_empty() => ("{}".to_string(), false),
+        Value::Object(map) => (format!("{{{} keys}}", map.len()), true),
+        Value::String(s) => (format!("\"{}\"", s), false),
+        other => (other.to_string(), false),
+    };
+    let is_expanded = expandable && expanded.contains(&path);
+
+    rows.push(AttributeRow {
+        path: path.clone(),
+        depth,
+        key,
+        value: summary,
+        expandable,
+        expanded: is_expanded,
+    });
+
+    if !is_expanded {
+        return;
+    }
+    match value {
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                push_rows(
+                    rows,
+                    format!("{}/{}", path, i),
+                    None,
+                    item,
+                    depth + 1,
+                    expanded,
+                );
+            }
+        }
+        Value::Object(map) => {
+            for (k, v) in map {
+                push_rows(
+                    rows,
+                    format!("{}/{}", path, k),
+                    Some(k.clone()),
+                    v,
+                    depth + 1,
+                    expanded,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Labels the Details cursor walks before the attribute tree: visibility, then deps
+pub fn label_entries(visibility: &[String], deps: &[String]) -> Vec<String> {
+    visibility.iter().chain(deps).cloned().collect()
+}
+
+/// Cursor of the Details pane and folding of the attribute tree, reset when another
+/// target is selected. The cursor covers the label entries first, then the tree rows.
+#[derive(Debug, Clone, Default)]
+pub struct AttributeTreeState {
+    pub target: String,
+    pub cursor: usize,
+    pub expanded: HashSet<String>,
+}
+
+impl AttributeTreeState {
+    /// Start over when the tree is shown for another target
+    pub fn sync(&mut self, target: &str) {
+        if self.target != target {
+            self.target = target.to_string();
+            self.cursor = 0;
+            self.expanded.clear();
+        }
+    }
+
+    pub fn next(&mut self, row_count: usize) {
+        if row_count > 0 {
+            self.cursor = (self.cursor + 1) % row_count;
+        }
+    }
+
+    pub fn prev(&mut self, row_count: usize) {
+        if row_count > 0 {
+            self.cursor = (self.cursor + row_count - 1) % row_count;
+        }
+    }
+
+    /// Label entry under the cursor, if it is on one
+    pub fn selected_label<'a>(&self, labels: &'a [String]) -> Option<&'a str> {
+        labels.get(self.cursor).map(|label| label.as_str())
+    }
+
+    /// Fold or unfold the list or dict under the cursor, rows start at `first_row`
+    pub fn toggle(&mut self, rows: &[AttributeRow], first_row: usize) {
+        let Some(row) = self
+            .cursor
+            .checked_sub(first_row)
+            .and_then(|index| rows.get(index))
+        else {
+            return;
+        };
+        if !row.expandable {
+            return;
+        }
+        if !self.expanded.remove(&row.path) {
+            self.expanded.insert(row.path.clone());
+        }
+    }
+}
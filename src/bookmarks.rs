@@ -0,0 +1,140 @@
+use anyhow::Context;
+use anyhow::Result;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::Path;
+use std::path::PathBuf;
+use tracing::warn;
+
+use crate::jump_list::Location;
+
+/// A directory or target marked with a character
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub mark: char,
+    pub location: Location,
+}
+
+/// Marks of a project, saved as JSON under the state directory after every change
+pub struct Bookmarks {
+    // Sorted by mark
+    pub marks: Vec<Bookmark>,
+    file: Option<PathBuf>,
+}
+
+impl Bookmarks {
+    /// Load the marks saved for the project at `project_root`, none when there are no saved marks
+    pub fn load(project_root: &Path) -> Self {
+        let file = crate::project_state_file(project_root, "bookmarks.json");
+        let marks = file
+            .as_ref()
+            .and_then(|file| std::fs::read_to_string(file).ok())
+            .and_then(|content| match serde_json::from_str(&content) {
+                Ok(marks) => Some(marks),
+                Err(e) => {
+                    warn!("Ignoring unreadable bookmarks: {}", e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+        Self { marks, file }
+    }
+
+    pub fn get(&self, mark: char) -> Option<&Bookmark> {
+        self.marks.iter().find(|b| b.mark == mark)
+    }
+
+    /// Set `mark` to `location`, replacing what it pointed to
+    pub fn set(&mut self, mark: char, location: Location) -> Result<()> {
+        self.marks.retain(|b| b.mark != mark);
+        self.marks.push(Bookmark { mark, location });
+        self.marks.sort_by_key(|b| b.mark);
+        self.save()
+    }
+
+    pub fn remove(&mut self, index: usize) -> Result<()> {
+        if index < self.marks.len() {
+            self.marks.remove(index);
+        }
+        self.save()
+    }
+
+    fn save(&self) -> Result<()> {
+        let file = self
+            .file
+            .as_ref()
+            .context("Failed to determine the state directory")?;
+        if let Some(dir) = file.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        std::fs::write(file, serde_json::to_string_pretty(&self.marks)?)
+            .with_context(|| format!("Failed to write {}", file.display()))
+    }
+}
+
+/// Keys the bookmarks popup uses itself, a mark with one of these names could not be typed there
+pub const RESERVED_MARKS: [char; 3] = ['j', 'k', 'd'];
+
+/// Popup listing the marks. Typing a mark jumps to it, `d` deletes the selected one.
+pub struct BookmarksView {
+    pub active: bool,
+    pub selected: usize,
+}
+
+/// What the user did in the bookmarks popup
+pub enum BookmarkAction {
+    Jump(Location),
+    Delete(usize),
+}
+
+impl BookmarksView {
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            selected: 0,
+        }
+    }
+
+    pub fn open(&mut self) {
+        self.active = true;
+        self.selected = 0;
+    }
+
+    pub fn close(&mut self) {
+        self.active = false;
+    }
+
+    /// Handle a key while the popup is open
+    pub fn handle_key(&mut self, key: KeyEvent, bookmarks: &Bookmarks) -> Option<BookmarkAction> {
+        let count = bookmarks.marks.len();
+        match key.code {
+            KeyCode::Esc => self.close(),
+            KeyCode::Down | KeyCode::Char('j') if count > 0 => {
+                self.selected = (self.selected + 1) % count;
+            }
+            KeyCode::Up | KeyCode::Char('k') if count > 0 => {
+                self.selected = (self.selected + count - 1) % count;
+            }
+            KeyCode::Char('d') if self.selected < count => {
+                let index = self.selected;
+                self.selected = self.selected.min(count.saturating_sub(2));
+                return Some(BookmarkAction::Delete(index));
+            }
+            KeyCode::Enter => {
+                let bookmark = bookmarks.marks.get(self.selected)?;
+                self.close();
+                return Some(BookmarkAction::Jump(bookmark.location.clone()));
+            }
+            KeyCode::Char(c) => {
+                let bookmark = bookmarks.get(c)?;
+                self.close();
+                return Some(BookmarkAction::Jump(bookmark.location.clone()));
+            }
+            _ => {}
+        }
+        None
+    }
+}
@@ -5,6 +5,7 @@ use nerd_font_symbols::dev as dev_symbols;
 use serde::Deserialize;
 use serde::Serialize;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::Command;
@@ -13,11 +14,109 @@ use tokio::sync::Mutex;
 use tracing::debug;
 use tracing::warn;
 
+use crate::build_file::BuildFileNames;
+use crate::build_file::BuildFileResults;
+use crate::changes::Vcs;
+use crate::editor::EditorRequest;
+use crate::editor::EditorRequests;
+use crate::filter::TargetFilter;
+use crate::jump_list::JumpList;
+use crate::jump_list::Location;
+use crate::recent::ActionResult;
+use crate::recent::RecentAction;
+use crate::recent::RecentTargets;
 use crate::scheduler::Priority;
 use crate::scheduler::Scheduler;
 use crate::scheduler::Task;
 use crate::scheduler::TaskId;
 
+/// Outcome of a scheduled command: stdout on success, stderr on failure.
+/// Filled by the task callbacks and polled from the UI loop.
+pub type CommandOutput = Arc<Mutex<Option<Result<String>>>>;
+
+/// Create a task that runs `cmds` in `current_dir` and stores the outcome in `output`
+pub fn create_command_task(cmds: Vec<String>, current_dir: PathBuf, output: CommandOutput) -> Task {
+    let failure_output = output.clone();
+
+    let task_on_success = Box::new(move |stdout: String| {
+        async move {
+            *output.lock().await = Some(Ok(stdout));
+        }
+        .boxed()
+    });
+
+    let task_on_failure = Box::new(move |stderr: String| {
+        async move {
+            *failure_output.lock().await = Some(Err(anyhow!(stderr.trim().to_string())));
+        }
+        .boxed()
+    });
+
+    Task::new(Priority::Normal, cmds, current_dir, task_on_success).with_on_failure(task_on_failure)
+}
+
+/// Resolve the repository root that buck-relative paths (`uquery --stack` locations, cell
+/// paths) are relative to: `buck2 root --kind project`, then the closest git, Sapling or
+/// Mercurial checkout containing `start`.
+pub fn resolve_repo_root(start: &Path) -> Option<PathBuf> {
+    let output = Command::new("buck2")
+        .args(["root", "--kind", "project"])
+        .current_dir(start)
+        .stdin(std::process::Stdio::null())
+        .output();
+    match output {
+        Ok(output) if output.status.success() => {
+            let root = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if !root.is_empty() {
+                return Some(PathBuf::from(root));
+            }
+        }
+        Ok(output) => debug!(
+            "buck2 root failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+        Err(e) => debug!("Failed to run buck2 root: {}", e),
+    }
+
+    Vcs::detect(start).map(|(_, root)| root)
+}
+
+/// Split buck2 query output into target labels.
+/// `cquery` prints `label (configuration)`, only the label part is kept.
+pub fn parse_query_labels(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(|label| label.to_string())
+        .collect()
+}
+
+/// Split a cquery label like `cell//foo:bar (cfg//platforms:linux#abc)` into the label and
+/// its configuration
+pub fn split_configured_label(label: &str) -> (&str, Option<&str>) {
+    match label.split_once(" (") {
+        Some((label, configuration)) => (label, Some(configuration.trim_end_matches(')'))),
+        None => (label, None),
+    }
+}
+
+/// Details of the selected target resolved by `buck2 cquery` for the session's platform
+#[derive(Debug, Clone)]
+pub enum ConfiguredTarget {
+    Loading,
+    Loaded {
+        configuration: String,
+        deps: Vec<String>,
+    },
+    Failed(String),
+}
+
+// (platform, label, details)
+type ConfiguredResults = Arc<Mutex<Vec<(String, String, Result<ConfiguredTarget>)>>>;
+
+// (package directory, targets)
+pub type TargetResults = Arc<Mutex<Vec<(PathBuf, Result<Vec<BuckTarget>, TargetsLoadError>)>>>;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BuckTarget {
     pub full_target_label_name: String,
@@ -31,6 +130,9 @@ pub struct BuckTarget {
     pub oncall: Option<String>,
     pub visibility: Vec<String>,
     pub default_target_platform: Option<String>,
+    pub labels: Vec<String>,
+    // Every attribute of the `buck2 targets -A` output, including the ones extracted above
+    pub attributes: serde_json::Map<String, serde_json::Value>,
 }
 
 impl BuckTarget {
@@ -85,6 +187,17 @@ impl BuckTarget {
             .and_then(|v| v.as_str())
             .map(|s| s.to_string());
 
+        let labels = json
+            .get("labels")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(|s| s.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
         let pkg = package.as_deref().unwrap_or("");
         let full_target_label_name = format!("{pkg}:{}", name);
 
@@ -99,6 +212,8 @@ impl BuckTarget {
             oncall,
             visibility,
             default_target_platform,
+            labels,
+            attributes: json.as_object().cloned().unwrap_or_default(),
         }
     }
 
@@ -149,6 +264,77 @@ impl BuckTarget {
     }
 }
 
+/// Why the targets of a package could not be listed, with the build file line buck2
+/// blamed when it reported one
+#[derive(Debug, Clone)]
+pub struct TargetsLoadError {
+    pub message: String,
+    pub file: Option<PathBuf>,
+    pub line: Option<u32>,
+}
+
+impl TargetsLoadError {
+    /// Pick the error and the innermost location out of the stderr of `buck2 targets`.
+    /// Locations are relative to the project root.
+    fn from_stderr(stderr: &str, repo_root: &Path) -> Self {
+        let mut location = None;
+        let mut span_found = false;
+        for line in stderr.lines().map(str::trim) {
+            // Diagnostic span, e.g. `--> foo/BUCK:5:10`, wins over the traceback
+            if let Some(span) = line.strip_prefix("-->") {
+                let mut parts = span.trim().rsplitn(3, ':');
+                let (_column, line_number, file) = (parts.next(), parts.next(), parts.next());
+                if let (Some(file), Some(Ok(line_number))) =
+                    (file, line_number.map(str::parse::<u32>))
+                {
+                    location = Some((file.to_string(), line_number));
+                    span_found = true;
+                }
+            } else if !span_found
+                && let Some(frame) = line.strip_prefix('*')
+                && let Some(file_part) = frame.split(',').next()
+                && let Some((file, line_number)) = file_part.trim().rsplit_once(':')
+                && let Ok(line_number) = line_number.parse::<u32>()
+            {
+                // Traceback frames are listed outermost first, keep the last one
+                location = Some((file.to_string(), line_number));
+            }
+        }
+
+        // Skip the lines buck2 prints around every failure
+        const NOISE: [&str; 6] = [
+            "Buck UI:",
+            "Network:",
+            "Build ID:",
+            "Command failed",
+            "Caused by:",
+            "Jobs completed",
+        ];
+        let lines: Vec<&str> = stderr
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !NOISE.iter().any(|n| line.starts_with(n)))
+            .collect();
+        let message = lines
+            .iter()
+            .find_map(|line| line.strip_prefix("error:"))
+            .or(lines.last().copied())
+            .unwrap_or("buck2 targets failed")
+            .trim()
+            .to_string();
+
+        let (file, line) = match location {
+            Some((file, line)) => (Some(repo_root.join(file)), Some(line)),
+            None => (None, None),
+        };
+        Self {
+            message,
+            file,
+            line,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BuckDirectory {
     pub path: PathBuf,
@@ -156,6 +342,7 @@ pub struct BuckDirectory {
     pub has_buck_file: bool,
     pub targets_loaded: bool,
     pub targets_loading: bool,
+    pub load_error: Option<TargetsLoadError>,
 }
 
 pub struct UICurrentDirectory {
@@ -165,15 +352,13 @@ pub struct UICurrentDirectory {
 }
 
 impl UICurrentDirectory {
-    pub fn new(current_path: &PathBuf) -> Self {
+    pub fn new(current_path: &PathBuf, build_files: &BuildFileNames) -> Self {
         let mut sub_directories = Vec::new();
         let mut dir_to_index = HashMap::new();
 
         if let Ok(entries) = std::fs::read_dir(current_path) {
             // Add current directory as "."
-            let buck_file = current_path.join("BUCK");
-            let targets_file = current_path.join("TARGETS");
-            let has_buck_file = buck_file.exists() || targets_file.exists();
+            let has_buck_file = build_files.has_build_file(current_path);
 
             let current_dir = BuckDirectory {
                 path: current_path.clone(),
@@ -181,6 +366,7 @@ impl UICurrentDirectory {
                 has_buck_file,
                 targets_loaded: false,
                 targets_loading: false,
+                load_error: None,
             };
 
             sub_directories.push(current_dir);
@@ -189,9 +375,7 @@ impl UICurrentDirectory {
             for entry in entries.filter_map(|e| e.ok()) {
                 if entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false) {
                     let path = entry.path();
-                    let buck_file = path.join("BUCK");
-                    let buck2_file: PathBuf = path.join("BUCK2");
-                    let has_buck_file = buck_file.exists() || buck2_file.exists();
+                    let has_buck_file = build_files.has_build_file(&path);
 
                     let dir = BuckDirectory {
                         path: path.clone(),
@@ -199,6 +383,7 @@ impl UICurrentDirectory {
                         has_buck_file,
                         targets_loaded: false,
                         targets_loading: false,
+                        load_error: None,
                     };
 
                     sub_directories.push(dir);
@@ -269,16 +454,49 @@ impl BuckDirectory {
 
 pub struct BuckProject {
     pub root_path: PathBuf,
+    // Root of the repository, buck-relative paths are resolved against it
+    pub repo_root: PathBuf,
     pub current_path: PathBuf,
     pub directories: HashMap<PathBuf, BuckDirectory>,
     pub selected_directory: PathBuf,
     pub selected_target: usize,
     pub filtered_targets: Vec<BuckTarget>,
+    // Search query the listed targets are narrowed to, kept across directories
+    pub target_filter: Option<String>,
     pub cells: HashMap<String, PathBuf>,
+    // Build file names of each cell, decides which directories are packages
+    pub build_files: BuildFileNames,
+
+    // Target name to select once the selected directory finishes loading
+    pending_target_name: Option<String>,
+    // Why the last navigation to a label could not select its target, shown by the UI
+    pub navigation_error: Option<String>,
+    // Locations left by navigation, for back/forward
+    pub jump_list: JumpList,
+    // Recently inspected, opened, built and tested targets
+    pub recent: RecentTargets,
+    // Labels of the targets selected for batch actions, in the order they were selected.
+    // They can span several packages.
+    pub target_selection: Vec<String>,
+    // Directory and target index where the visual range started, the range runs to the
+    // selected target while the directory stays selected
+    pub visual_anchor: Option<(PathBuf, usize)>,
+
+    // Session-wide target platform, queries go through cquery when it is set
+    pub target_platform: Option<String>,
+    pub configured_targets: HashMap<String, ConfiguredTarget>,
+    configured_results: ConfiguredResults,
+
+    // Files to open, launched from the UI loop
+    pub editor_requests: EditorRequests,
+
+    // Package directories marked in the explorer, e.g. the ones with changed targets
+    pub highlighted_packages: HashSet<PathBuf>,
 
     // Scheduler integration
-    pub target_results: Arc<Mutex<Vec<(PathBuf, Result<Vec<BuckTarget>>)>>>,
+    pub target_results: TargetResults,
     active_load_tasks: HashMap<PathBuf, TaskId>,
+    build_file_results: BuildFileResults,
 }
 
 impl BuckProject {
@@ -301,31 +519,59 @@ impl BuckProject {
             )
         })?;
 
-        let current_path = root_path.clone();
-        let selected_directory = current_path.clone();
+        let repo_root = resolve_repo_root(&root_path).unwrap_or_else(|| {
+            warn!(
+                "Could not find the repository root, using {}",
+                root_path.display()
+            );
+            root_path.clone()
+        });
 
-        let mut project = Self {
+        let mut project = Self::with_paths(root_path, repo_root);
+        project.load_cells().await?;
+
+        Ok(project)
+    }
+
+    /// Project at `root_path` with nothing loaded yet
+    fn with_paths(root_path: PathBuf, repo_root: PathBuf) -> Self {
+        let recent = RecentTargets::load(&repo_root);
+        Self {
+            current_path: root_path.clone(),
+            selected_directory: root_path.clone(),
             root_path,
-            current_path,
+            repo_root,
             directories: HashMap::new(),
-            selected_directory,
             selected_target: 0,
             filtered_targets: Vec::new(),
+            target_filter: None,
             cells: HashMap::new(),
+            build_files: BuildFileNames::new(),
+            pending_target_name: None,
+            navigation_error: None,
+            jump_list: JumpList::default(),
+            recent,
+            target_selection: Vec::new(),
+            visual_anchor: None,
+            target_platform: None,
+            configured_targets: HashMap::new(),
+            configured_results: Arc::new(Mutex::new(Vec::new())),
+            editor_requests: Arc::new(Mutex::new(Vec::new())),
+            highlighted_packages: HashSet::new(),
             target_results: Arc::new(Mutex::new(Vec::new())),
+            build_file_results: Arc::new(Mutex::new(Vec::new())),
             active_load_tasks: HashMap::new(),
-        };
-
-        project.load_cells().await?;
-
-        Ok(project)
+        }
     }
 
     fn create_target_loading_task(
         path: PathBuf,
-        results: Arc<Mutex<Vec<(PathBuf, Result<Vec<BuckTarget>>)>>>,
+        repo_root: PathBuf,
+        results: TargetResults,
     ) -> Task {
         let path_clone = path.clone();
+        let failure_path = path.clone();
+        let failure_results = results.clone();
 
         let task_on_success = Box::new(|result: String| {
             async move {
@@ -335,6 +581,16 @@ impl BuckProject {
             }
             .boxed()
         });
+        let task_on_failure = Box::new(move |stderr: String| {
+            async move {
+                let error = TargetsLoadError::from_stderr(&stderr, &repo_root);
+                failure_results
+                    .lock()
+                    .await
+                    .push((failure_path, Err(error)));
+            }
+            .boxed()
+        });
 
         Task::new(
             Priority::Normal,
@@ -347,6 +603,7 @@ impl BuckProject {
             path.clone(),
             task_on_success,
         )
+        .with_on_failure(task_on_failure)
     }
 
     pub fn request_targets_for_directory(&mut self, dir: PathBuf, scheduler: &Scheduler) {
@@ -371,7 +628,11 @@ impl BuckProject {
         }
 
         // Create and dispatch new task
-        let task = Self::create_target_loading_task(dir.clone(), self.target_results.clone());
+        let task = Self::create_target_loading_task(
+            dir.clone(),
+            self.repo_root.clone(),
+            self.target_results.clone(),
+        );
         let task_id = task.id;
 
         scheduler.dispatch_micro(task);
@@ -406,14 +667,19 @@ impl BuckProject {
             match result {
                 Ok(targets) => {
                     dir.targets = targets;
-                    dir.targets_loaded = true;
+                    dir.load_error = None;
                 }
-                Err(_) => {
-                    // Keep empty targets on error
+                Err(error) => {
+                    warn!(
+                        "Failed to load targets of {}: {}",
+                        dir_path.display(),
+                        error.message
+                    );
                     dir.targets = Vec::new();
-                    dir.targets_loaded = true;
+                    dir.load_error = Some(error);
                 }
             }
+            dir.targets_loaded = true;
 
             debug!(
                 "is current selected dir: {}, dir_indxe: {}, self.selected_directory: {}",
@@ -429,6 +695,35 @@ impl BuckProject {
         }
     }
 
+    /// Read the build file names of the cells in the background
+    pub fn request_build_file_names(&self, scheduler: &Scheduler) {
+        BuildFileNames::request(&self.cells, &self.build_file_results, scheduler);
+    }
+
+    /// Pick up the build file names of the cells, called from the UI loop. Directories seen
+    /// with the default names are checked again.
+    pub async fn update_build_file_results(&mut self, scheduler: &Scheduler) {
+        if !self
+            .build_files
+            .update_results(&self.build_file_results)
+            .await
+        {
+            return;
+        }
+        for dir in self.directories.values_mut() {
+            dir.has_buck_file = self.build_files.has_build_file(&dir.path);
+        }
+        // The selected directory may have just turned out to be a package
+        let selected_is_package = self.build_files.has_build_file(&self.selected_directory);
+        let selected_loaded = self
+            .directories
+            .get(&self.selected_directory)
+            .is_some_and(|dir| dir.targets_loaded || dir.targets_loading);
+        if selected_is_package && !selected_loaded {
+            self.update_targets_for_selected_directory(scheduler);
+        }
+    }
+
     async fn load_cells(&mut self) -> Result<()> {
         let output = Command::new("buck2")
             .arg("audit")
@@ -446,7 +741,7 @@ impl BuckProject {
                 Ok(cells_data) => {
                     self.cells = cells_data
                         .into_iter()
-                        .map(|(name, path)| (name, PathBuf::from(path)))
+                        .map(|(name, path)| (name, self.repo_root.join(path)))
                         .collect();
                 }
                 Err(e) => {
@@ -464,38 +759,22 @@ impl BuckProject {
         Ok(())
     }
 
-    fn parse_buck2_targets_output_static(output: &str, dir_path: &Path) -> Result<Vec<BuckTarget>> {
-        let mut targets = Vec::new();
-
-        // Try to parse as JSON first (for -A output)
-        if let Ok(json_array) = serde_json::from_str::<Vec<serde_json::Value>>(output) {
-            for json in json_array {
-                targets.push(BuckTarget::from_json_value(&json, dir_path));
-            }
-        } else {
-            // Fallback to line-by-line parsing for legacy output
-            for line in output.lines() {
-                let line = line.trim();
-                if line.is_empty() {
-                    continue;
-                }
-
-                targets.push(BuckTarget {
-                    full_target_label_name: line.to_string(),
-                    name: line.to_string(),
-                    rule_type: "unknown".to_string(),
-                    path: dir_path.to_path_buf(),
-                    deps: Vec::new(),
-                    details_loaded: false,
-                    package: None,
-                    oncall: None,
-                    visibility: Vec::new(),
-                    default_target_platform: None,
-                });
+    fn parse_buck2_targets_output_static(
+        output: &str,
+        dir_path: &Path,
+    ) -> Result<Vec<BuckTarget>, TargetsLoadError> {
+        let json_array = serde_json::from_str::<Vec<serde_json::Value>>(output).map_err(|e| {
+            TargetsLoadError {
+                message: format!("Unexpected buck2 targets output: {}", e),
+                file: None,
+                line: None,
             }
-        }
+        })?;
 
-        Ok(targets)
+        Ok(json_array
+            .iter()
+            .map(|json| BuckTarget::from_json_value(json, dir_path))
+            .collect())
     }
 
     pub fn update_filtered_targets(&mut self) {
@@ -503,9 +782,15 @@ impl BuckProject {
     }
 
     fn update_filtered_targets_with_reset(&mut self, reset_selection: bool) {
-        // Get all targets from the currently selected directory (no filtering)
+        // Get the targets of the currently selected directory matching the filter, if any
+        let filter = self.target_filter.as_deref().map(TargetFilter::parse);
         self.filtered_targets = if let Some(selected_dir) = self.get_selected_directory() {
-            selected_dir.targets.clone()
+            selected_dir
+                .targets
+                .iter()
+                .filter(|target| filter.as_ref().is_none_or(|f| f.matches(target)))
+                .cloned()
+                .collect()
         } else {
             Vec::new()
         };
@@ -521,6 +806,58 @@ impl BuckProject {
                 self.selected_target = self.filtered_targets.len() - 1;
             }
         }
+
+        self.apply_pending_target_selection();
+    }
+
+    /// Narrow the listed targets to the ones matching `query`, or list them all again.
+    /// The selected target stays selected when it still matches.
+    pub fn set_target_filter(&mut self, query: Option<String>) {
+        let selected = self
+            .get_selected_target()
+            .map(|target| target.full_target_label_name.clone());
+        self.target_filter = query;
+        self.update_filtered_targets_with_reset(false);
+        if let Some(index) = selected.and_then(|label| {
+            self.filtered_targets
+                .iter()
+                .position(|target| target.full_target_label_name == label)
+        }) {
+            self.selected_target = index;
+        }
+    }
+
+    /// Select the target requested by `navigate_to_label` once its package is loaded
+    fn apply_pending_target_selection(&mut self) {
+        let Some(name) = &self.pending_target_name else {
+            return;
+        };
+        let loaded = self
+            .get_selected_directory()
+            .is_some_and(|dir| dir.targets_loaded);
+        if !loaded {
+            return;
+        }
+
+        match self
+            .filtered_targets
+            .iter()
+            .position(|t| t.target_name() == *name)
+        {
+            Some(index) => self.selected_target = index,
+            None => {
+                warn!(
+                    "Target {} not found in {}",
+                    name,
+                    self.selected_directory.display()
+                );
+                let package = self
+                    .package_for_path(&self.selected_directory)
+                    .unwrap_or_else(|| self.selected_directory.display().to_string());
+                self.navigation_error = Some(format!("No target {} in {}", name, package));
+            }
+        }
+        self.pending_target_name = None;
     }
 
     pub fn get_selected_directory(&self) -> Option<&BuckDirectory> {
@@ -531,13 +868,16 @@ impl BuckProject {
         let selected_dir = self.get_selected_directory()?;
 
         // Get the absolute path of the selected directory
-        let current_path = selected_dir.abs_path();
+        self.cell_for_path(&selected_dir.abs_path())
+    }
 
+    /// Find the innermost cell containing the absolute `path`
+    pub fn cell_for_path(&self, path: &Path) -> Option<&str> {
         let mut best_match: Option<(&str, usize)> = None;
 
         for (cell_name, cell_path) in &self.cells {
-            // Check if cell_path is a prefix of current_path
-            if current_path.starts_with(cell_path) {
+            // Check if cell_path is a prefix of path
+            if path.starts_with(cell_path) {
                 // Get the number of components in the cell_path
                 let cell_components_count = cell_path.components().count();
 
@@ -554,14 +894,21 @@ impl BuckProject {
         best_match.map(|(name, _)| name)
     }
 
-    pub fn get_selected_buck_package_name(&self) -> Option<String> {
-        let cell = self.current_cell()?;
+    /// Name of the cell whose root is `dir`, cell boundaries are marked in the explorer
+    pub fn cell_rooted_at(&self, dir: &Path) -> Option<&str> {
+        self.cells
+            .iter()
+            .find(|(_, cell_path)| cell_path.as_path() == dir)
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Format the absolute `path` as a buck package, e.g. `cell//path/to/dir`
+    pub fn package_for_path(&self, path: &Path) -> Option<String> {
+        let cell = self.cell_for_path(path)?;
         let cell_path = self.cells.get(cell)?;
-        let selected_dir = self.get_selected_directory()?;
-        let current_path = selected_dir.abs_path();
 
-        // Strip the cell path from the current path
-        let relative_path = current_path.strip_prefix(cell_path).ok()?;
+        // Strip the cell path from the path
+        let relative_path = path.strip_prefix(cell_path).ok()?;
 
         // Convert to string and format as cell//path
         if relative_path.as_os_str().is_empty() {
@@ -574,6 +921,249 @@ impl BuckProject {
         }
     }
 
+    /// Whether `dir` is or contains one of the highlighted packages
+    pub fn is_highlighted(&self, dir: &Path) -> bool {
+        self.highlighted_packages
+            .iter()
+            .any(|package| package.starts_with(dir))
+    }
+
+    pub fn get_selected_buck_package_name(&self) -> Option<String> {
+        let selected_dir = self.get_selected_directory()?;
+        self.package_for_path(&selected_dir.abs_path())
+    }
+
+    /// Resolve a label like `cell//path/to/pkg:name` into the package directory and the
+    /// target name. `//path:name` is resolved against the current cell.
+    pub fn resolve_label(&self, label: &str) -> Result<(PathBuf, Option<String>)> {
+        // Drop a trailing configuration, e.g. `cell//foo:bar (cfg//:linux)`
+        let label = label.split_whitespace().next().unwrap_or_default();
+
+        let (cell, rest) = label
+            .split_once("//")
+            .ok_or_else(|| anyhow!("Not a target label: {}", label))?;
+
+        let cell_path = if cell.is_empty() {
+            self.current_cell()
+                .and_then(|cell| self.cells.get(cell))
+                .unwrap_or(&self.repo_root)
+        } else {
+            self.cells
+                .get(cell)
+                .ok_or_else(|| anyhow!("Unknown cell: {}", cell))?
+        };
+
+        let (package, name) = match rest.split_once(':') {
+            Some((package, name)) => (package, Some(name.to_string())),
+            None => (rest, None),
+        };
+
+        let dir = cell_path.join(package);
+        if !dir.is_dir() {
+            return Err(anyhow!(
+                "Package directory does not exist: {}",
+                dir.display()
+            ));
+        }
+
+        Ok((dir, name))
+    }
+
+    /// Turn what the user typed into a full label: `cell//path:name` is kept, `//path:name`
+    /// is in the cell of the current directory, `:name` is taken from the selected package
+    /// and `path/to/dir[:name]` is a directory relative to the current one
+    pub fn absolute_label(&self, input: &str) -> Result<String> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(anyhow!("Empty label"));
+        }
+        if input.starts_with("//")
+            && let Some(cell) = self.cell_for_path(&self.current_path)
+        {
+            return Ok(format!("{}{}", cell, input));
+        }
+        if input.contains("//") {
+            return Ok(input.to_string());
+        }
+
+        let (path, name) = match input.split_once(':') {
+            Some((path, name)) => (path, Some(name)),
+            None => (input, None),
+        };
+        let dir = if path.is_empty() {
+            self.selected_directory.clone()
+        } else {
+            self.current_path
+                .join(path)
+                .canonicalize()
+                .map_err(|_| anyhow!("No such directory: {}", path))?
+        };
+        let package = self
+            .package_for_path(&dir)
+            .ok_or_else(|| anyhow!("{} is not inside a cell", dir.display()))?;
+
+        Ok(match name {
+            Some(name) => format!("{}:{}", package, name),
+            None => package,
+        })
+    }
+
+    /// Navigate to the package of `label` and select the target once it has loaded
+    pub fn navigate_to_label(&mut self, label: &str, scheduler: &Scheduler) -> Result<()> {
+        let (dir, name) = self.resolve_label(label)?;
+        if name.is_some() && !self.build_files.has_build_file(&dir) {
+            return Err(anyhow!("{} has no build file", dir.display()));
+        }
+
+        self.navigate_to_directory(dir, scheduler);
+        // The targets may already be cached, otherwise this is applied when they arrive
+        self.pending_target_name = name;
+        self.apply_pending_target_selection();
+        Ok(())
+    }
+
+    /// `buck2 uquery <query>`, or `buck2 cquery <query>` for the session's target platform
+    pub fn query_command(&self, query: String) -> Vec<String> {
+        match &self.target_platform {
+            Some(platform) => vec![
+                "buck2".to_owned(),
+                "cquery".to_owned(),
+                query,
+                "--target-platforms".to_owned(),
+                platform.clone(),
+            ],
+            None => vec!["buck2".to_owned(), "uquery".to_owned(), query],
+        }
+    }
+
+    /// Switch the session to `platform`, or back to the unconfigured graph with `None`
+    pub fn set_target_platform(&mut self, platform: Option<String>) {
+        if self.target_platform != platform {
+            self.target_platform = platform;
+            self.configured_targets.clear();
+        }
+    }
+
+    /// Resolve the selected target with cquery when a platform is chosen and it isn't known yet
+    pub fn request_configured_target(&mut self, scheduler: &Scheduler) {
+        let Some(platform) = self.target_platform.clone() else {
+            return;
+        };
+        let Some(label) = self
+            .get_selected_target()
+            .map(|t| t.full_target_label_name.clone())
+        else {
+            return;
+        };
+        if self.configured_targets.contains_key(&label) {
+            return;
+        }
+        self.configured_targets
+            .insert(label.clone(), ConfiguredTarget::Loading);
+
+        let mut cmds = self.query_command(label.clone());
+        cmds.push("--output-all-attributes".to_owned());
+
+        let results = self.configured_results.clone();
+        let failure_results = results.clone();
+        let failure_label = label.clone();
+        let failure_platform = platform.clone();
+
+        let task_on_success = Box::new(move |output: String| {
+            async move {
+                let configured = Self::parse_configured_target(&output);
+                results.lock().await.push((platform, label, configured));
+            }
+            .boxed()
+        });
+        let task_on_failure = Box::new(move |stderr: String| {
+            async move {
+                failure_results.lock().await.push((
+                    failure_platform,
+                    failure_label,
+                    Err(anyhow!(stderr.trim().to_string())),
+                ));
+            }
+            .boxed()
+        });
+
+        let task = Task::new(
+            Priority::Normal,
+            cmds,
+            self.root_path.clone(),
+            task_on_success,
+        )
+        .with_on_failure(task_on_failure);
+        scheduler.dispatch_micro(task);
+    }
+
+    fn parse_configured_target(output: &str) -> Result<ConfiguredTarget> {
+        let json: serde_json::Map<String, serde_json::Value> = serde_json::from_str(output)
+            .map_err(|e| anyhow!("Unexpected buck2 cquery output: {}", e))?;
+        let (label, attrs) = json
+            .iter()
+            .next()
+            .ok_or_else(|| anyhow!("Target is not compatible with the platform"))?;
+
+        let configuration = split_configured_label(label)
+            .1
+            .unwrap_or_default()
+            .to_string();
+        let deps = attrs
+            .get("buck.deps")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(|s| split_configured_label(s).0.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(ConfiguredTarget::Loaded {
+            configuration,
+            deps,
+        })
+    }
+
+    /// Pick up finished cquery lookups, called from the UI loop
+    pub async fn update_configured_target_results(&mut self) {
+        let results = {
+            let mut results = self.configured_results.lock().await;
+            std::mem::take(&mut *results)
+        };
+
+        for (platform, label, result) in results {
+            // Drop lookups made for a previous platform
+            if self.target_platform.as_ref() != Some(&platform) {
+                continue;
+            }
+            let configured = result.unwrap_or_else(|e| ConfiguredTarget::Failed(e.to_string()));
+            self.configured_targets.insert(label, configured);
+        }
+    }
+
+    /// Deps shown for `target`: the configured ones once cquery resolved them for the
+    /// session's platform, the unconfigured ones otherwise
+    pub fn displayed_deps<'a>(&'a self, target: &'a BuckTarget) -> &'a [String] {
+        if self.target_platform.is_some()
+            && let Some(ConfiguredTarget::Loaded { deps, .. }) =
+                self.configured_targets.get(&target.full_target_label_name)
+        {
+            return deps;
+        }
+        &target.deps
+    }
+
+    /// Look up a target by label among the packages loaded so far
+    pub fn find_loaded_target(&self, label: &str) -> Option<&BuckTarget> {
+        self.directories
+            .values()
+            .filter(|dir| dir.targets_loaded)
+            .flat_map(|dir| dir.targets.iter())
+            .find(|target| target.full_target_label_name == label)
+    }
+
     pub fn get_selected_target(&self) -> Option<&BuckTarget> {
         self.filtered_targets.get(self.selected_target)
     }
@@ -602,9 +1192,7 @@ impl BuckProject {
             for entry in entries.filter_map(|e| e.ok()) {
                 if entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false) {
                     let path = entry.path();
-                    let buck_file = path.join("BUCK");
-                    let targets_file = path.join("TARGETS");
-                    let has_buck_file = buck_file.exists() || targets_file.exists();
+                    let has_buck_file = self.build_files.has_build_file(&path);
 
                     dirs.push(BuckDirectory {
                         path,
@@ -612,6 +1200,7 @@ impl BuckProject {
                         has_buck_file,
                         targets_loaded: false,
                         targets_loading: false,
+                        load_error: None,
                     });
                 }
             }
@@ -622,10 +1211,79 @@ impl BuckProject {
     }
 
     pub fn get_current_directories(&self) -> UICurrentDirectory {
-        UICurrentDirectory::new(&self.current_path)
+        UICurrentDirectory::new(&self.current_path, &self.build_files)
+    }
+
+    /// Where the explorer is, as recorded in the jump list
+    pub fn location(&self) -> Location {
+        Location {
+            current_path: self.current_path.clone(),
+            selected_directory: self.selected_directory.clone(),
+            target: self
+                .get_selected_target()
+                .map(|t| t.full_target_label_name.clone()),
+        }
+    }
+
+    /// Remember the current location before jumping away from it
+    pub fn record_jump(&mut self) {
+        let location = self.location();
+        self.jump_list.record(location);
+    }
+
+    /// Go back to the previous location of the jump list. Returns false at its start.
+    pub fn jump_back(&mut self, scheduler: &Scheduler) -> bool {
+        let current = self.location();
+        match self.jump_list.back(current) {
+            Some(location) => {
+                self.restore_location(location, scheduler);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Go forward again after `jump_back`. Returns false at the end of the jump list.
+    pub fn jump_forward(&mut self, scheduler: &Scheduler) -> bool {
+        match self.jump_list.forward() {
+            Some(location) => {
+                self.restore_location(location, scheduler);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Go to the entry at `index` of the jump list
+    pub fn jump_to_entry(&mut self, index: usize, scheduler: &Scheduler) {
+        if self.jump_list.position == self.jump_list.entries.len() {
+            // Keep the live location reachable with forward
+            self.record_jump();
+        }
+        if let Some(location) = self.jump_list.go_to(index) {
+            self.restore_location(location, scheduler);
+        }
+    }
+
+    /// Show `location` again without recording a jump, the target is selected once loaded
+    pub fn restore_location(&mut self, location: Location, scheduler: &Scheduler) {
+        self.current_path = location.current_path;
+        self.selected_directory = location.selected_directory;
+        self.selected_target = 0;
+        self.filtered_targets.clear();
+        self.pending_target_name = location
+            .target
+            .as_deref()
+            .and_then(|label| label.rsplit_once(':'))
+            .map(|(_, name)| name.to_string());
+
+        self.update_targets_for_selected_directory(scheduler);
+        self.apply_pending_target_selection();
     }
 
     pub fn navigate_to_directory(&mut self, dir_path: PathBuf, scheduler: &Scheduler) {
+        self.record_jump();
+
         // Convert to absolute path for consistent navigation
         let absolute_path = dir_path.canonicalize().unwrap_or(dir_path);
 
@@ -633,6 +1291,7 @@ impl BuckProject {
         self.selected_directory = absolute_path;
         self.selected_target = 0;
         self.filtered_targets.clear();
+        self.pending_target_name = None;
 
         // Request targets for the new current directory
         self.update_targets_for_selected_directory(scheduler);
@@ -652,7 +1311,11 @@ impl BuckProject {
                 // Clear targets if directory doesn't have Buck files
                 self.filtered_targets.clear();
                 self.selected_target = 0;
+                // No targets will load to select it in
+                self.pending_target_name = None;
             }
+        } else {
+            self.pending_target_name = None;
         }
     }
 
@@ -663,9 +1326,7 @@ impl BuckProject {
         }
 
         // If not found, add it
-        let buck_file = path.join("BUCK");
-        let targets_file = path.join("TARGETS");
-        let has_buck_file = buck_file.exists() || targets_file.exists();
+        let has_buck_file = self.build_files.has_build_file(path);
 
         let new_dir = BuckDirectory {
             path: path.clone(),
@@ -673,10 +1334,186 @@ impl BuckProject {
             has_buck_file,
             targets_loaded: false,
             targets_loading: false,
+            load_error: None,
         };
         self.directories.insert(path.clone(), new_dir);
     }
 
+    /// Targets of the visual range, if one was started in the selected directory
+    pub fn visual_range(&self) -> Option<std::ops::RangeInclusive<usize>> {
+        let (dir, anchor) = self.visual_anchor.as_ref()?;
+        if *dir != self.selected_directory {
+            return None;
+        }
+        let anchor = (*anchor).min(self.filtered_targets.len().saturating_sub(1));
+        Some(anchor.min(self.selected_target)..=anchor.max(self.selected_target))
+    }
+
+    /// Whether the target at `index` of the listed targets is selected or in the visual range
+    pub fn is_target_selected(&self, index: usize) -> bool {
+        self.visual_range()
+            .is_some_and(|range| range.contains(&index))
+            || self.filtered_targets.get(index).is_some_and(|target| {
+                self.target_selection
+                    .contains(&target.full_target_label_name)
+            })
+    }
+
+    /// Selected targets, including the visual range
+    pub fn selected_labels(&self) -> Vec<String> {
+        let mut labels = self.target_selection.clone();
+        if let Some(range) = self.visual_range() {
+            for target in self.filtered_targets.get(range).unwrap_or_default() {
+                if !labels.contains(&target.full_target_label_name) {
+                    labels.push(target.full_target_label_name.clone());
+                }
+            }
+        }
+        labels
+    }
+
+    /// Targets batch actions run on: the selection, or the selected target when nothing is
+    /// selected
+    pub fn action_labels(&self) -> Vec<String> {
+        let labels = self.selected_labels();
+        if !labels.is_empty() {
+            return labels;
+        }
+        self.get_selected_target()
+            .map(|target| vec![target.full_target_label_name.clone()])
+            .unwrap_or_default()
+    }
+
+    pub fn has_selection(&self) -> bool {
+        !self.target_selection.is_empty() || self.visual_range().is_some()
+    }
+
+    /// Add the selected target to the selection, or take it out when it is already in
+    pub fn toggle_target_selection(&mut self) {
+        let Some(label) = self
+            .get_selected_target()
+            .map(|target| target.full_target_label_name.clone())
+        else {
+            return;
+        };
+        if let Some(position) = self.target_selection.iter().position(|l| *l == label) {
+            self.target_selection.remove(position);
+        } else {
+            self.target_selection.push(label);
+        }
+    }
+
+    /// Start a visual range at the selected target, or add the running one to the selection.
+    /// Returns whether a range was started.
+    pub fn toggle_visual_range(&mut self) -> bool {
+        if self.visual_range().is_some() {
+            self.target_selection = self.selected_labels();
+            self.visual_anchor = None;
+            return false;
+        }
+        if self.get_selected_target().is_none() {
+            return false;
+        }
+        self.visual_anchor = Some((self.selected_directory.clone(), self.selected_target));
+        true
+    }
+
+    /// Select every target of the selected package, or unselect them when they all are
+    pub fn toggle_package_selection(&mut self) {
+        let labels: Vec<String> = self
+            .filtered_targets
+            .iter()
+            .map(|target| target.full_target_label_name.clone())
+            .collect();
+        if labels
+            .iter()
+            .all(|label| self.target_selection.contains(label))
+        {
+            self.target_selection
+                .retain(|label| !labels.contains(label));
+        } else {
+            for label in labels {
+                if !self.target_selection.contains(&label) {
+                    self.target_selection.push(label);
+                }
+            }
+        }
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.target_selection.clear();
+        self.visual_anchor = None;
+    }
+
+    /// Run `buck2 build` or `buck2 test` on `labels` in a single invocation. The outcome is
+    /// recorded in the recent targets.
+    pub fn run_target_action(
+        &mut self,
+        labels: Vec<String>,
+        action: RecentAction,
+        scheduler: &Scheduler,
+    ) {
+        let Some(command) = action.command() else {
+            return;
+        };
+        if labels.is_empty() {
+            return;
+        }
+        self.recent
+            .record_all(&labels, action, ActionResult::Running);
+
+        let mut cmds = vec!["buck2".to_owned(), command.to_owned()];
+        cmds.extend(labels.iter().cloned());
+        debug!("Running {}", cmds.join(" "));
+
+        let success_results = self.recent.results.clone();
+        let success_labels = labels.clone();
+        let task_on_success = Box::new(move |_output: String| {
+            async move {
+                success_results.lock().await.push((
+                    success_labels,
+                    action,
+                    ActionResult::Succeeded,
+                ));
+            }
+            .boxed()
+        });
+        let failure_results = self.recent.results.clone();
+        let task_on_failure = Box::new(move |stderr: String| {
+            async move {
+                let reason = stderr
+                    .lines()
+                    .map(str::trim)
+                    .rfind(|line| !line.is_empty())
+                    .unwrap_or("failed")
+                    .to_string();
+                failure_results
+                    .lock()
+                    .await
+                    .push((labels, action, ActionResult::Failed(reason)));
+            }
+            .boxed()
+        });
+
+        let task = Task::new(
+            Priority::Normal,
+            cmds,
+            self.repo_root.clone(),
+            task_on_success,
+        )
+        .with_on_failure(task_on_failure);
+        scheduler.dispatch_micro(task);
+    }
+
+    /// Queue `file` to be opened in the editor
+    pub async fn open_in_editor(&self, file: PathBuf, line: Option<u32>) {
+        self.editor_requests.lock().await.push(EditorRequest {
+            file,
+            line,
+            label: None,
+        });
+    }
+
     pub fn open_target_definition(&self, scheduler: &Scheduler) {
         let Some(target) = self.get_selected_target() else {
             warn!("No target selected for opening definition");
@@ -687,6 +1524,9 @@ impl BuckProject {
         debug!("Opening definition for target: {}", target_name);
 
         // Create task to run buck2 uquery --stack
+        let editor_requests = self.editor_requests.clone();
+        let repo_root = self.repo_root.clone();
+        let label = target_name.clone();
         let task_on_success = Box::new(move |output: String| {
             async move {
                 debug!("uquery output:\n{}", output);
@@ -695,59 +1535,12 @@ impl BuckProject {
                 if let Some((file_path, line_number)) = Self::parse_uquery_stack_output(&output) {
                     debug!("Extracted: path={}, line={}", file_path, line_number);
 
-                    // Get hg root directory
-                    let hg_root = match std::process::Command::new("hg")
-                        .arg("root")
-                        .output()
-                    {
-                        Ok(output) => {
-                            String::from_utf8_lossy(&output.stdout)
-                                .trim()
-                                .to_string()
-                        }
-                        Err(e) => {
-                            warn!("Failed to get hg root: {}", e);
-                            return;
-                        }
-                    };
-
-                    debug!("hg root: {}", hg_root);
-
-                    // Open file in VS Code using `code -g file:line`
-                    let file_with_line = format!("{}:{}", file_path, line_number);
-                    debug!("Opening with VS Code: {}", file_with_line);
-
-                    if let Err(e) = std::process::Command::new("code")
-                        .arg("-g")
-                        .arg(&file_with_line)
-                        .current_dir(&hg_root)
-                        .spawn()
-                    {
-                        warn!("Failed to open in VS Code: {}", e);
-                    }
-
-                    // OLD IMPLEMENTATION: Open the fb-vscode URI
-                    // let uri = format!(
-                    //     "fb-vscode://nuclide.core/open-arc?project=fbsource&path={}&line={}",
-                    //     file_path, line_number
-                    // );
-                    // debug!("Opening URI: {}", uri);
-                    //
-                    // // Use xdg-open on Linux, open on macOS, start on Windows
-                    // let open_cmd = if cfg!(target_os = "linux") {
-                    //     "xdg-open"
-                    // } else if cfg!(target_os = "macos") {
-                    //     "open"
-                    // } else {
-                    //     "start"
-                    // };
-                    //
-                    // if let Err(e) = std::process::Command::new(open_cmd)
-                    //     .arg(&uri)
-                    //     .spawn()
-                    // {
-                    //     warn!("Failed to open URI: {}", e);
-                    // }
+                    // Stack locations are relative to the project root
+                    editor_requests.lock().await.push(EditorRequest {
+                        file: repo_root.join(file_path),
+                        line: Some(line_number),
+                        label: Some(label),
+                    });
                 } else {
                     warn!("Failed to parse uquery output");
                 }
@@ -799,3 +1592,224 @@ impl BuckProject {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_error_prefers_the_diagnostic_span() {
+        let stderr = "\
+Build ID: 0c4a3e2b-6f1d-4c55-9a0e-3b7d2f8e1a90
+Network: Up: 0B  Down: 0B
+Command failed: 
+Error loading targets in package `root//app` for target pattern `root//app:`
+
+Caused by:
+    0: Error evaluating build file: `root//app:BUCK`
+    1: Traceback (most recent call last):
+         * app/BUCK:3, in <module>
+             rust_binary(
+       error: Missing named-only parameter `name` for call to `rust_binary`
+        --> app/BUCK:3:1
+         |
+       3 | / rust_binary(
+       4 | |     srcs = [\"main.rs\"],
+       5 | | )
+         | |_^
+         |
+";
+        let error = TargetsLoadError::from_stderr(stderr, Path::new("/repo"));
+        assert_eq!(
+            error.message,
+            "Missing named-only parameter `name` for call to `rust_binary`"
+        );
+        assert_eq!(error.file, Some(PathBuf::from("/repo/app/BUCK")));
+        assert_eq!(error.line, Some(3));
+    }
+
+    #[test]
+    fn load_error_keeps_the_innermost_traceback_frame() {
+        let stderr = "\
+Command failed: 
+Error evaluating build file: `root//lib:BUCK`
+
+Caused by:
+    Traceback (most recent call last):
+      * lib/BUCK:12, in <module>
+          rust_library_wrapper(name = \"lib\")
+      * defs/wrappers.bzl:40, in rust_library_wrapper
+          fail(\"missing srcs\")
+    error: fail: missing srcs
+";
+        let error = TargetsLoadError::from_stderr(stderr, Path::new("/repo"));
+        assert_eq!(error.message, "fail: missing srcs");
+        assert_eq!(error.file, Some(PathBuf::from("/repo/defs/wrappers.bzl")));
+        assert_eq!(error.line, Some(40));
+    }
+
+    #[test]
+    fn load_error_span_after_traceback_wins() {
+        let stderr = "\
+Traceback (most recent call last):
+  * lib/BUCK:2, in <module>
+      load_macro()
+  * defs.bzl:7, in load_macro
+      undefined_function()
+error: Variable `undefined_function` not found
+ --> defs.bzl:7:5
+  |
+7 |     undefined_function()
+  |     ^^^^^^^^^^^^^^^^^^
+  |
+";
+        let error = TargetsLoadError::from_stderr(stderr, Path::new("/repo"));
+        assert_eq!(error.message, "Variable `undefined_function` not found");
+        assert_eq!(error.file, Some(PathBuf::from("/repo/defs.bzl")));
+        assert_eq!(error.line, Some(7));
+    }
+
+    #[test]
+    fn load_error_without_location_uses_the_last_line() {
+        let stderr = "\
+Build ID: 0c4a3e2b-6f1d-4c55-9a0e-3b7d2f8e1a90
+Command failed: 
+Caused by:
+    Unknown cell: `foo`
+";
+        let error = TargetsLoadError::from_stderr(stderr, Path::new("/repo"));
+        assert_eq!(error.message, "Unknown cell: `foo`");
+        assert_eq!(error.file, None);
+        assert_eq!(error.line, None);
+
+        let error = TargetsLoadError::from_stderr("", Path::new("/repo"));
+        assert_eq!(error.message, "buck2 targets failed");
+    }
+
+    /// Project in a scratch directory with a `root` cell and a nested `tp` cell, browsing
+    /// `root//app` with `root//app/sub` selected
+    struct LabelFixture {
+        dir: PathBuf,
+        project: BuckProject,
+    }
+
+    impl LabelFixture {
+        fn new(name: &str) -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("buck-tui-{}-{}", name, std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            let dir = dir.canonicalize().unwrap();
+            let repo = dir.join("repo");
+            for package in ["app/sub", "lib", "third-party/zlib"] {
+                std::fs::create_dir_all(repo.join(package)).unwrap();
+            }
+            let mut project = BuckProject::with_paths(repo.clone(), repo.clone());
+            project.cells = HashMap::from([
+                ("root".to_string(), repo.clone()),
+                ("tp".to_string(), repo.join("third-party")),
+            ]);
+            project.current_path = repo.join("app");
+            project.selected_directory = repo.join("app/sub");
+            Self { dir, project }
+        }
+
+        fn repo(&self, path: &str) -> PathBuf {
+            self.dir.join("repo").join(path)
+        }
+    }
+
+    impl Drop for LabelFixture {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    #[test]
+    fn resolves_label_forms() {
+        let fixture = LabelFixture::new("resolve");
+        let cases = [
+            ("root//app:main", "app", Some("main")),
+            ("root//app", "app", None),
+            ("tp//zlib:zlib", "third-party/zlib", Some("zlib")),
+            ("//lib:lib", "lib", Some("lib")),
+            (
+                "root//app/sub:test (cfg//:linux#abc)",
+                "app/sub",
+                Some("test"),
+            ),
+        ];
+        for (label, dir, name) in cases {
+            let resolved = fixture.project.resolve_label(label).unwrap();
+            assert_eq!(
+                resolved,
+                (fixture.repo(dir), name.map(str::to_string)),
+                "{}",
+                label
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_unresolvable_labels() {
+        let fixture = LabelFixture::new("resolve-errors");
+        let cases = [
+            ("other//app:main", "Unknown cell: other"),
+            (":main", "Not a target label: :main"),
+        ];
+        for (label, message) in cases {
+            let error = fixture.project.resolve_label(label).unwrap_err();
+            assert_eq!(error.to_string(), message, "{}", label);
+        }
+        let error = fixture
+            .project
+            .resolve_label("root//missing:t")
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("Package directory does not exist")
+        );
+    }
+
+    #[test]
+    fn absolutizes_goto_input() {
+        let fixture = LabelFixture::new("absolute");
+        let cases = [
+            ("root//app:main", "root//app:main"),
+            ("  tp//zlib:zlib ", "tp//zlib:zlib"),
+            // Unknown cells are left for resolve_label to report
+            ("other//app:main", "other//app:main"),
+            ("//lib:lib", "root//lib:lib"),
+            (":test", "root//app/sub:test"),
+            ("sub:test", "root//app/sub:test"),
+            ("sub", "root//app/sub"),
+            ("../third-party/zlib:zlib", "tp//zlib:zlib"),
+            (".", "root//app"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                fixture.project.absolute_label(input).unwrap(),
+                expected,
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_goto_input_outside_cells() {
+        let fixture = LabelFixture::new("absolute-errors");
+        let cases = [
+            ("", "Empty label".to_string()),
+            ("missing:t", "No such directory: missing".to_string()),
+            (
+                "../..:t",
+                format!("{} is not inside a cell", fixture.dir.display()),
+            ),
+        ];
+        for (input, message) in cases {
+            let error = fixture.project.absolute_label(input).unwrap_err();
+            assert_eq!(error.to_string(), message, "{:?}", input);
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use futures::FutureExt;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::debug;
+
+use crate::scheduler::Priority;
+use crate::scheduler::Scheduler;
+use crate::scheduler::Task;
+
+// What buck2 looks for when a cell doesn't set `buildfile.name`
+const DEFAULT_NAMES: [&str; 4] = ["BUCK.v2", "BUCK", "TARGETS.v2", "TARGETS"];
+
+// (cell root, build file names) read from the buckconfig of each cell
+pub type BuildFileResults = Arc<Mutex<Vec<(PathBuf, Vec<String>)>>>;
+
+/// Build file names of every cell, from `buildfile.name` in its buckconfig.
+/// The one policy deciding whether a directory is a package.
+#[derive(Debug, Clone)]
+pub struct BuildFileNames {
+    // Cell root and its names, innermost cells win
+    cells: Vec<(PathBuf, Vec<String>)>,
+    default: Vec<String>,
+}
+
+impl BuildFileNames {
+    pub fn new() -> Self {
+        Self {
+            cells: Vec::new(),
+            default: DEFAULT_NAMES.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    /// Ask buck2 for the configured names of every cell, through the scheduler so startup
+    /// doesn't wait on one buck2 call per cell. The default names apply until they arrive.
+    pub fn request(
+        cells: &HashMap<String, PathBuf>,
+        results: &BuildFileResults,
+        scheduler: &Scheduler,
+    ) {
+        for cell_path in cells.values() {
+            let results = results.clone();
+            let success_path = cell_path.clone();
+            let task_on_success = Box::new(move |output: String| {
+                async move {
+                    if let Some(names) = Self::parse_config(&output) {
+                        debug!("Build files of {}: {:?}", success_path.display(), names);
+                        results.lock().await.push((success_path, names));
+                    }
+                }
+                .boxed()
+            });
+            let task = Task::new(
+                Priority::Normal,
+                vec![
+                    "buck2".to_owned(),
+                    "audit".to_owned(),
+                    "config".to_owned(),
+                    "buildfile.name".to_owned(),
+                ],
+                cell_path.clone(),
+                task_on_success,
+            );
+            scheduler.dispatch_micro(task);
+        }
+    }
+
+    /// Take in the names that arrived, called from the UI loop. Returns whether any did.
+    pub async fn update_results(&mut self, results: &BuildFileResults) -> bool {
+        let arrived = std::mem::take(&mut *results.lock().await);
+        if arrived.is_empty() {
+            return false;
+        }
+        for (cell_path, names) in arrived {
+            self.cells.retain(|(path, _)| *path != cell_path);
+            self.cells.push((cell_path, names));
+        }
+        true
+    }
+
+    /// Names out of `buck2 audit config buildfile.name`. Each configured name also matches
+    /// its `.v2` variant, which buck2 prefers when both exist.
+    fn parse_config(output: &str) -> Option<Vec<String>> {
+        // Printed as `name = BUCK` under a `[buildfile]` header, or `buildfile.name = BUCK`
+        let value = output.lines().find_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            let key = key.trim();
+            (key == "name" || key == "buildfile.name").then(|| value.trim().to_string())
+        })?;
+
+        let names: Vec<String> = value
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .flat_map(|name| [format!("{}.v2", name), name.to_string()])
+            .collect();
+        (!names.is_empty()).then_some(names)
+    }
+
+    /// Names used in the innermost cell containing `dir`
+    pub fn names_for(&self, dir: &Path) -> &[String] {
+        self.cells
+            .iter()
+            .filter(|(cell_path, _)| dir.starts_with(cell_path))
+            .max_by_key(|(cell_path, _)| cell_path.components().count())
+            .map(|(_, names)| names.as_slice())
+            .unwrap_or(&self.default)
+    }
+
+    pub fn is_build_file(&self, dir: &Path, name: &str) -> bool {
+        self.names_for(dir).iter().any(|n| n == name)
+    }
+
+    /// Whether `dir` is a package
+    pub fn has_build_file(&self, dir: &Path) -> bool {
+        self.names_for(dir)
+            .iter()
+            .any(|name| dir.join(name).is_file())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_section_and_dotted_output() {
+        let section = "# cell root\n[buildfile]\n    name = BUCK\n";
+        assert_eq!(
+            BuildFileNames::parse_config(section),
+            Some(vec!["BUCK.v2".to_string(), "BUCK".to_string()])
+        );
+        let dotted = "buildfile.name = TARGETS, BUCK\n";
+        assert_eq!(
+            BuildFileNames::parse_config(dotted),
+            Some(vec![
+                "TARGETS.v2".to_string(),
+                "TARGETS".to_string(),
+                "BUCK.v2".to_string(),
+                "BUCK".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn unset_name_keeps_the_defaults() {
+        assert_eq!(BuildFileNames::parse_config(""), None);
+        assert_eq!(BuildFileNames::parse_config("buildfile.name =\n"), None);
+        assert_eq!(
+            BuildFileNames::parse_config("[buildfile]\n  includes = //defs.bzl\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn innermost_cell_names_win() {
+        let mut names = BuildFileNames::new();
+        names.cells = vec![
+            (PathBuf::from("/repo"), vec!["TARGETS".to_string()]),
+            (PathBuf::from("/repo/third-party"), vec!["BUCK".to_string()]),
+        ];
+        assert_eq!(names.names_for(Path::new("/repo/app")), ["TARGETS"]);
+        assert_eq!(
+            names.names_for(Path::new("/repo/third-party/zlib")),
+            ["BUCK"]
+        );
+        assert_eq!(names.names_for(Path::new("/elsewhere")), DEFAULT_NAMES);
+        assert!(names.is_build_file(Path::new("/repo/app"), "TARGETS"));
+        assert!(!names.is_build_file(Path::new("/repo/app"), "BUCK"));
+    }
+}
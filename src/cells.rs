@@ -0,0 +1,105 @@
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use std::path::PathBuf;
+
+use crate::buck::BuckProject;
+
+/// Switcher listing every cell of `buck2 audit cell`, typing filters by name or path
+pub struct CellBrowser {
+    pub active: bool,
+    pub input: String,
+    // (name, root) of every cell, sorted by name
+    pub cells: Vec<(String, PathBuf)>,
+    // Indices into `cells` matching the input
+    pub visible: Vec<usize>,
+    pub selected: usize,
+    // Cell of the current directory
+    pub current_cell: Option<String>,
+    pub repo_root: PathBuf,
+}
+
+impl CellBrowser {
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            input: String::new(),
+            cells: Vec::new(),
+            visible: Vec::new(),
+            selected: 0,
+            current_cell: None,
+            repo_root: PathBuf::new(),
+        }
+    }
+
+    /// Open the browser with the cell of the current directory preselected
+    pub fn open(&mut self, project: &BuckProject) {
+        self.active = true;
+        self.input.clear();
+        self.repo_root = project.repo_root.clone();
+        self.current_cell = project
+            .cell_for_path(&project.current_path)
+            .map(|cell| cell.to_string());
+
+        self.cells = project
+            .cells
+            .iter()
+            .map(|(name, path)| (name.clone(), path.clone()))
+            .collect();
+        self.cells.sort();
+
+        self.update_visible();
+        self.selected = self
+            .visible
+            .iter()
+            .position(|&i| Some(&self.cells[i].0) == self.current_cell.as_ref())
+            .unwrap_or(0);
+    }
+
+    pub fn close(&mut self) {
+        self.active = false;
+    }
+
+    /// Handle a key while the browser is open.
+    /// Returns the name and root of the cell the user picked, if any.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<(String, PathBuf)> {
+        match key.code {
+            KeyCode::Esc => self.close(),
+            KeyCode::Down if !self.visible.is_empty() => {
+                self.selected = (self.selected + 1) % self.visible.len();
+            }
+            KeyCode::Up if !self.visible.is_empty() => {
+                self.selected = (self.selected + self.visible.len() - 1) % self.visible.len();
+            }
+            KeyCode::Enter => {
+                let &index = self.visible.get(self.selected)?;
+                self.close();
+                return Some(self.cells[index].clone());
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+                self.update_visible();
+            }
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                self.update_visible();
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn update_visible(&mut self) {
+        let input = self.input.trim().to_lowercase();
+        self.visible = self
+            .cells
+            .iter()
+            .enumerate()
+            .filter(|(_, (name, path))| {
+                name.to_lowercase().contains(&input)
+                    || path.to_string_lossy().to_lowercase().contains(&input)
+            })
+            .map(|(i, _)| i)
+            .collect();
+        self.selected = 0;
+    }
+}
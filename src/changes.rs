@@ -0,0 +1,325 @@
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::buck::BuckProject;
+use crate::buck::CommandOutput;
+use crate::buck::create_command_task;
+use crate::query_console::QueryResults;
+use crate::recent::RecentAction;
+use crate::recent::RecentTargets;
+use crate::scheduler::Scheduler;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Vcs {
+    Git,
+    Sapling,
+    Mercurial,
+}
+
+impl Vcs {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Vcs::Git => "git",
+            Vcs::Sapling => "sl",
+            Vcs::Mercurial => "hg",
+        }
+    }
+
+    /// Closest git, Sapling or Mercurial checkout containing `path` and its root
+    pub fn detect(path: &Path) -> Option<(Self, PathBuf)> {
+        path.ancestors().find_map(|dir| {
+            let vcs = if dir.join(".git").exists() {
+                Vcs::Git
+            } else if dir.join(".sl").exists() {
+                Vcs::Sapling
+            } else if dir.join(".hg").exists() {
+                Vcs::Mercurial
+            } else {
+                return None;
+            };
+            Some((vcs, dir.to_path_buf()))
+        })
+    }
+
+    /// Status listing NUL-terminated entries, so paths are printed as they are instead of
+    /// quoted and escaped
+    fn status_command(&self) -> Vec<String> {
+        let args: &[&str] = match self {
+            Vcs::Git => &[
+                "git",
+                "status",
+                "--porcelain",
+                "-z",
+                "--untracked-files=all",
+            ],
+            Vcs::Sapling => &["sl", "status", "--print0"],
+            Vcs::Mercurial => &["hg", "status", "--print0"],
+        };
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    /// Paths of the modified, added and untracked files, relative to the checkout root.
+    /// Deleted files are skipped, nothing owns them anymore.
+    fn parse_status(&self, output: &str) -> Vec<String> {
+        let mut files = Vec::new();
+        let mut entries = output.split('\0').filter(|entry| !entry.is_empty());
+        while let Some(entry) = entries.next() {
+            let Some((status, path)) = (match self {
+                Vcs::Git => entry.get(..2).zip(entry.get(3..)),
+                Vcs::Sapling | Vcs::Mercurial => entry.split_once(' '),
+            }) else {
+                continue;
+            };
+            let deleted = match self {
+                Vcs::Git => {
+                    // Renames and copies are followed by the original path, as its own entry
+                    if status.contains(['R', 'C']) {
+                        entries.next();
+                    }
+                    status.contains('D')
+                }
+                Vcs::Sapling | Vcs::Mercurial => matches!(status, "R" | "!"),
+            };
+            if !deleted {
+                files.push(path.to_string());
+            }
+        }
+        files
+    }
+}
+
+/// Targets touched by the working copy changes: the owners of the changed files,
+/// optionally extended to everything depending on them within a universe
+pub struct ChangedTargetsView {
+    pub active: bool,
+    pub vcs: Option<Vcs>,
+    pub files: Vec<String>,
+    pub include_rdeps: bool,
+    pub universe: String,
+    pub editing_universe: bool,
+    pub universe_input: String,
+    pub loading_status: bool,
+    pub status_error: Option<String>,
+    pub results: QueryResults,
+    checkout_root: PathBuf,
+    // buck2 runs from the project root, the changed files are relative to it
+    repo_root: PathBuf,
+    status_output: CommandOutput,
+}
+
+impl ChangedTargetsView {
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            vcs: None,
+            files: Vec::new(),
+            include_rdeps: false,
+            universe: String::new(),
+            editing_universe: false,
+            universe_input: String::new(),
+            loading_status: false,
+            status_error: None,
+            results: QueryResults::new(),
+            checkout_root: PathBuf::new(),
+            repo_root: PathBuf::new(),
+            status_output: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Open the view and collect the changed files again
+    pub fn open(&mut self, project: &BuckProject, scheduler: &Scheduler) {
+        self.active = true;
+        self.editing_universe = false;
+        if self.universe.is_empty() {
+            let cell = project
+                .cell_for_path(&project.current_path)
+                .unwrap_or_default();
+            self.universe = format!("{}//...", cell);
+        }
+        self.refresh(project, scheduler);
+    }
+
+    pub fn close(&mut self) {
+        self.active = false;
+    }
+
+    fn refresh(&mut self, project: &BuckProject, scheduler: &Scheduler) {
+        self.files.clear();
+        self.results.labels.clear();
+        self.status_error = None;
+
+        let Some((vcs, root)) = Vcs::detect(&project.repo_root) else {
+            self.vcs = None;
+            self.status_error = Some("Not inside a git, Sapling or Mercurial checkout".to_string());
+            return;
+        };
+        self.vcs = Some(vcs);
+        self.checkout_root = root;
+        self.repo_root = project.repo_root.clone();
+
+        let task = create_command_task(
+            vcs.status_command(),
+            self.checkout_root.clone(),
+            self.status_output.clone(),
+        );
+        scheduler.dispatch_micro(task);
+        self.loading_status = true;
+    }
+
+    /// Handle a key while the view is open.
+    /// Returns the label of the target the user picked, if any.
+    pub fn handle_key(
+        &mut self,
+        key: KeyEvent,
+        project: &mut BuckProject,
+        scheduler: &Scheduler,
+    ) -> Option<String> {
+        if self.editing_universe {
+            match key.code {
+                KeyCode::Esc => self.editing_universe = false,
+                KeyCode::Enter => {
+                    self.editing_universe = false;
+                    self.universe = self.universe_input.trim().to_string();
+                    self.run_owner_query(scheduler);
+                }
+                KeyCode::Backspace => {
+                    self.universe_input.pop();
+                }
+                KeyCode::Char(c) => self.universe_input.push(c),
+                _ => {}
+            }
+            return None;
+        }
+
+        match key.code {
+            KeyCode::Esc => self.close(),
+            KeyCode::Char('j') | KeyCode::Down => self.results.next(),
+            KeyCode::Char('k') | KeyCode::Up => self.results.prev(),
+            KeyCode::Char('r') => {
+                self.include_rdeps = !self.include_rdeps;
+                self.run_owner_query(scheduler);
+            }
+            KeyCode::Char('u') => {
+                self.universe_input = self.universe.clone();
+                self.editing_universe = true;
+            }
+            KeyCode::Char('R') => self.refresh(project, scheduler),
+            KeyCode::Char('b') => self.run_action(RecentAction::Built, project, scheduler),
+            KeyCode::Char('t') => self.run_action(RecentAction::Tested, project, scheduler),
+            KeyCode::Enter => return self.results.selected_label().map(|s| s.to_string()),
+            _ => {}
+        }
+        None
+    }
+
+    fn run_owner_query(&mut self, scheduler: &Scheduler) {
+        if self.files.is_empty() {
+            self.results.labels.clear();
+            return;
+        }
+
+        // %Ss expands to the set of the trailing arguments
+        let query = if self.include_rdeps {
+            format!("rdeps({}, owner(%Ss))", self.universe)
+        } else {
+            "owner(%Ss)".to_string()
+        };
+        let mut cmds = vec!["buck2".to_owned(), "uquery".to_owned(), query];
+        cmds.extend(self.files.iter().cloned());
+
+        self.results.run(cmds, self.repo_root.clone(), scheduler);
+    }
+
+    /// buck2 build/test of every listed target in one run, recorded in the recent targets
+    fn run_action(&self, action: RecentAction, project: &mut BuckProject, scheduler: &Scheduler) {
+        if self.results.labels.is_empty() || self.running_action(&project.recent).is_some() {
+            return;
+        }
+        project.run_target_action(self.results.labels.clone(), action, scheduler);
+    }
+
+    /// The build or test of the listed targets still running, if any
+    pub fn running_action(&self, recent: &RecentTargets) -> Option<RecentAction> {
+        self.results
+            .labels
+            .iter()
+            .find_map(|label| recent.running_action(label))
+    }
+
+    /// Pick up the VCS status and the owners, called from the UI loop.
+    /// Affected packages are highlighted in the explorer.
+    pub async fn update_results(&mut self, project: &mut BuckProject, scheduler: &Scheduler) {
+        let status = self.status_output.lock().await.take();
+        if let Some(status) = status {
+            self.loading_status = false;
+            match status {
+                Ok(output) => {
+                    // The checkout can be larger than the project, files outside of it
+                    // belong to no cell
+                    let paths = self
+                        .vcs
+                        .map(|vcs| vcs.parse_status(&output))
+                        .unwrap_or_default();
+                    self.files = paths
+                        .iter()
+                        .filter_map(|path| {
+                            let file = self.checkout_root.join(path);
+                            let relative = file.strip_prefix(&self.repo_root).ok()?;
+                            Some(relative.to_string_lossy().to_string())
+                        })
+                        .collect();
+                    self.run_owner_query(scheduler);
+                }
+                Err(e) => self.status_error = Some(e.to_string()),
+            }
+        }
+
+        if self.results.update().await {
+            project.highlighted_packages = self
+                .results
+                .labels
+                .iter()
+                .filter_map(|label| project.resolve_label(label).ok())
+                .map(|(dir, _)| dir)
+                .collect::<HashSet<_>>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn git_status_keeps_paths_verbatim() {
+        let output = " M src/a file.rs\0?? src/ünïcode.rs\0A  \"quoted\".rs\0";
+        assert_eq!(
+            Vcs::Git.parse_status(output),
+            vec!["src/a file.rs", "src/ünïcode.rs", "\"quoted\".rs"]
+        );
+    }
+
+    #[test]
+    fn git_status_skips_deleted_and_rename_sources() {
+        let output = " D gone.rs\0R  new.rs\0old.rs\0C  copy.rs\0orig.rs\0 M kept.rs\0";
+        assert_eq!(
+            Vcs::Git.parse_status(output),
+            vec!["new.rs", "copy.rs", "kept.rs"]
+        );
+    }
+
+    #[test]
+    fn hg_status_skips_removed_and_missing() {
+        let output = "M a b.rs\0R removed.rs\0! missing.rs\0? new.rs\0";
+        assert_eq!(
+            Vcs::Mercurial.parse_status(output),
+            vec!["a b.rs", "new.rs"]
+        );
+        assert_eq!(Vcs::Sapling.parse_status(output), vec!["a b.rs", "new.rs"]);
+    }
+}
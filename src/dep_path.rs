@@ -0,0 +1,475 @@
+use anyhow::Result;
+use anyhow::anyhow;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyModifiers;
+use serde_json::Map;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::buck::BuckProject;
+use crate::buck::CommandOutput;
+use crate::buck::create_command_task;
+use crate::buck::split_configured_label;
+use crate::scheduler::Scheduler;
+use crate::scheduler::TaskId;
+
+// allpaths() can explode on large graphs, only the first paths are listed
+const MAX_PATHS: usize = 100;
+const MAX_SUGGESTIONS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathQueryKind {
+    Somepath,
+    Allpaths,
+}
+
+impl PathQueryKind {
+    pub fn function(&self) -> &'static str {
+        match self {
+            PathQueryKind::Somepath => "somepath",
+            PathQueryKind::Allpaths => "allpaths",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DepPathFocus {
+    Input,
+    Paths,
+}
+
+/// One step of a dependency chain
+#[derive(Debug, Clone)]
+pub struct PathHop {
+    pub label: String,
+    // Attributes of the previous hop that reference this label (deps, exported_deps, ...)
+    pub via: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DependencyPath {
+    pub hops: Vec<PathHop>,
+    pub expanded: bool,
+}
+
+/// A row of the paths list: a path header or one of its hops
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathRow {
+    Header(usize),
+    Hop(usize, usize),
+}
+
+/// "Why does A depend on B?" view, backed by `buck2 uquery "somepath(A, B)"`/`allpaths`,
+/// or cquery once a target platform is picked
+pub struct DepPathView {
+    pub active: bool,
+    pub focus: DepPathFocus,
+    pub kind: PathQueryKind,
+    pub from: String,
+    pub to: String,
+    pub input: String,
+    pub suggestions: Vec<String>,
+    pub suggestion_selected: usize,
+    pub paths: Vec<DependencyPath>,
+    pub selected: usize,
+    pub running: bool,
+    pub error: Option<String>,
+    output: CommandOutput,
+    task_id: Option<TaskId>,
+}
+
+impl DepPathView {
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            focus: DepPathFocus::Input,
+            kind: PathQueryKind::Somepath,
+            from: String::new(),
+            to: String::new(),
+            input: String::new(),
+            suggestions: Vec::new(),
+            suggestion_selected: 0,
+            paths: Vec::new(),
+            selected: 0,
+            running: false,
+            error: None,
+            output: Arc::new(Mutex::new(None)),
+            task_id: None,
+        }
+    }
+
+    /// Open the view for paths starting at `from`, asking for the other end
+    pub fn open(&mut self, from: String, project: &BuckProject) {
+        self.active = true;
+        self.focus = DepPathFocus::Input;
+        if from != self.from {
+            self.paths.clear();
+            self.error = None;
+        }
+        self.from = from;
+        self.update_suggestions(project);
+    }
+
+    pub fn close(&mut self) {
+        self.active = false;
+    }
+
+    pub fn rows(&self) -> Vec<PathRow> {
+        let mut rows = Vec::new();
+        for (path_index, path) in self.paths.iter().enumerate() {
+            rows.push(PathRow::Header(path_index));
+            if path.expanded {
+                rows.extend((0..path.hops.len()).map(|hop| PathRow::Hop(path_index, hop)));
+            }
+        }
+        rows
+    }
+
+    /// Handle a key while the view is open.
+    /// Returns the label of the hop the user wants to jump to, if any.
+    pub fn handle_key(
+        &mut self,
+        key: KeyEvent,
+        project: &BuckProject,
+        scheduler: &Scheduler,
+    ) -> Option<String> {
+        if key.code == KeyCode::Esc {
+            self.close();
+            return None;
+        }
+
+        match self.focus {
+            DepPathFocus::Input => match key.code {
+                KeyCode::Enter => self.run(project, scheduler),
+                KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.kind = match self.kind {
+                        PathQueryKind::Somepath => PathQueryKind::Allpaths,
+                        PathQueryKind::Allpaths => PathQueryKind::Somepath,
+                    };
+                }
+                KeyCode::Down if !self.suggestions.is_empty() => {
+                    self.suggestion_selected =
+                        (self.suggestion_selected + 1) % self.suggestions.len();
+                }
+                KeyCode::Up if !self.suggestions.is_empty() => {
+                    self.suggestion_selected = (self.suggestion_selected + self.suggestions.len()
+                        - 1)
+                        % self.suggestions.len();
+                }
+                KeyCode::Tab => {
+                    if let Some(suggestion) = self.suggestions.get(self.suggestion_selected) {
+                        self.input = suggestion.clone();
+                        self.update_suggestions(project);
+                    } else if !self.paths.is_empty() {
+                        self.focus = DepPathFocus::Paths;
+                    }
+                }
+                KeyCode::Backspace => {
+                    self.input.pop();
+                    self.update_suggestions(project);
+                }
+                KeyCode::Char(c) => {
+                    self.input.push(c);
+                    self.update_suggestions(project);
+                }
+                _ => {}
+            },
+            DepPathFocus::Paths => {
+                let rows = self.rows();
+                match key.code {
+                    KeyCode::Char('j') | KeyCode::Down if !rows.is_empty() => {
+                        self.selected = (self.selected + 1) % rows.len();
+                    }
+                    KeyCode::Char('k') | KeyCode::Up if !rows.is_empty() => {
+                        self.selected = (self.selected + rows.len() - 1) % rows.len();
+                    }
+                    KeyCode::Tab | KeyCode::Char('i') => self.focus = DepPathFocus::Input,
+                    KeyCode::Char('l') | KeyCode::Char('h') | KeyCode::Char(' ') => {
+                        if let Some(row) = rows.get(self.selected) {
+                            self.toggle_path(*row);
+                        }
+                    }
+                    KeyCode::Enter => match rows.get(self.selected) {
+                        Some(PathRow::Hop(path, hop)) => {
+                            return Some(self.paths[*path].hops[*hop].label.clone());
+                        }
+                        Some(row) => self.toggle_path(*row),
+                        None => {}
+                    },
+                    _ => {}
+                }
+            }
+        }
+        None
+    }
+
+    fn toggle_path(&mut self, row: PathRow) {
+        let path = match row {
+            PathRow::Header(path) | PathRow::Hop(path, _) => path,
+        };
+        self.paths[path].expanded = !self.paths[path].expanded;
+        // Keep the cursor on the header when folding from one of the hops
+        if let Some(position) = self.rows().iter().position(|r| *r == PathRow::Header(path)) {
+            self.selected = position;
+        }
+    }
+
+    /// Offer loaded targets matching the typed text as the other end of the path
+    fn update_suggestions(&mut self, project: &BuckProject) {
+        self.suggestion_selected = 0;
+        let input = self.input.trim().to_lowercase();
+        if input.is_empty() {
+            self.suggestions.clear();
+            return;
+        }
+
+        let mut suggestions: Vec<String> = project
+            .directories
+            .values()
+            .flat_map(|dir| dir.targets.iter())
+            .map(|target| target.full_target_label_name.clone())
+            .filter(|label| label.to_lowercase().contains(&input) && *label != self.input)
+            .collect();
+        suggestions.sort();
+        suggestions.truncate(MAX_SUGGESTIONS);
+        self.suggestions = suggestions;
+    }
+
+    fn run(&mut self, project: &BuckProject, scheduler: &Scheduler) {
+        if self.input.trim().is_empty() {
+            return;
+        }
+        // The output has cell-qualified labels, so `to` has to be one too
+        let to = match project.absolute_label(&self.input) {
+            Ok(to) => to,
+            Err(e) => {
+                self.error = Some(e.to_string());
+                return;
+            }
+        };
+
+        if let Some(task_id) = self.task_id.take() {
+            scheduler.cancel(task_id);
+        }
+
+        let query = format!("{}({}, {})", self.kind.function(), self.from, to);
+        let mut cmds = project.query_command(query);
+        // Attributes are needed to rebuild the edges and tell which attribute carries them
+        cmds.push("--output-all-attributes".to_owned());
+        let task = create_command_task(cmds, project.current_path.clone(), self.output.clone());
+        self.task_id = Some(task.id);
+        scheduler.dispatch_micro(task);
+
+        self.to = to;
+        self.suggestions.clear();
+        self.running = true;
+        self.error = None;
+    }
+
+    /// Pick up the query output, called from the UI loop
+    pub async fn update_results(&mut self) {
+        let Some(result) = self.output.lock().await.take() else {
+            return;
+        };
+
+        self.running = false;
+        self.task_id = None;
+        self.selected = 0;
+        self.paths.clear();
+
+        match result.and_then(|output| Self::parse_paths(&output, &self.from, &self.to)) {
+            Ok(paths) if paths.is_empty() => {
+                self.error = Some(format!("{} does not depend on {}", self.from, self.to));
+            }
+            Ok(paths) => {
+                self.paths = paths;
+                // Show the first chain right away
+                self.paths[0].expanded = true;
+                self.focus = DepPathFocus::Paths;
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+
+    /// Rebuild the chains from `from` to `to` out of the `--output-all-attributes` JSON
+    fn parse_paths(output: &str, from: &str, to: &str) -> Result<Vec<DependencyPath>> {
+        let json: Map<String, Value> = serde_json::from_str(output)
+            .map_err(|e| anyhow!("Unexpected buck2 query output: {}", e))?;
+        // cquery labels carry their configuration, the chains are shown without it
+        let nodes: HashMap<&str, &Map<String, Value>> = json
+            .iter()
+            .filter_map(|(label, attrs)| {
+                Some((split_configured_label(label).0, attrs.as_object()?))
+            })
+            .collect();
+
+        let mut edges: HashMap<&str, Vec<(&str, Vec<String>)>> = HashMap::new();
+        for (label, attrs) in &nodes {
+            let deps = attrs
+                .get("buck.deps")
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|v| v.as_str())
+                .map(|dep| split_configured_label(dep).0)
+                .filter(|dep| nodes.contains_key(dep));
+
+            for dep in deps {
+                edges
+                    .entry(*label)
+                    .or_default()
+                    .push((dep, attributes_referencing(attrs, dep)));
+            }
+        }
+
+        let find = |wanted: &str| nodes.keys().copied().find(|label| *label == wanted);
+        // An empty result means there is no path
+        let Some(start) = find(from) else {
+            return Ok(Vec::new());
+        };
+        let end = find(to).ok_or_else(|| anyhow!("{} not in the result", to))?;
+
+        let mut paths = Vec::new();
+        let mut current = vec![PathHop {
+            label: start.to_string(),
+            via: Vec::new(),
+        }];
+        let mut on_path = HashSet::from([start]);
+        collect_paths(start, end, &edges, &mut current, &mut on_path, &mut paths);
+        Ok(paths)
+    }
+}
+
+fn collect_paths<'a>(
+    node: &'a str,
+    end: &str,
+    edges: &HashMap<&'a str, Vec<(&'a str, Vec<String>)>>,
+    current: &mut Vec<PathHop>,
+    on_path: &mut HashSet<&'a str>,
+    paths: &mut Vec<DependencyPath>,
+) {
+    if paths.len() >= MAX_PATHS {
+        return;
+    }
+    if node == end {
+        paths.push(DependencyPath {
+            hops: current.clone(),
+            expanded: false,
+        });
+        return;
+    }
+
+    for (dep, via) in edges.get(node).into_iter().flatten() {
+        if !on_path.insert(*dep) {
+            continue;
+        }
+        current.push(PathHop {
+            label: dep.to_string(),
+            via: via.clone(),
+        });
+        collect_paths(dep, end, edges, current, on_path, paths);
+        current.pop();
+        on_path.remove(*dep);
+    }
+}
+
+/// Names of the rule attributes whose value mentions `dep`
+fn attributes_referencing(attrs: &Map<String, Value>, dep: &str) -> Vec<String> {
+    attrs
+        .iter()
+        .filter(|(name, _)| !name.starts_with("buck."))
+        .filter(|(_, value)| value_mentions(value, dep))
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+fn value_mentions(value: &Value, dep: &str) -> bool {
+    match value {
+        Value::String(s) => split_configured_label(s).0 == dep,
+        Value::Array(values) => values.iter().any(|v| value_mentions(v, dep)),
+        Value::Object(map) => map.iter().any(|(k, v)| k == dep || value_mentions(v, dep)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hop_labels(path: &DependencyPath) -> Vec<&str> {
+        path.hops.iter().map(|hop| hop.label.as_str()).collect()
+    }
+
+    #[test]
+    fn rebuilds_multi_hop_chain() {
+        let output = r#"{
+            "root//app:main": {"buck.deps": ["root//lib:a"], "deps": ["root//lib:a"]},
+            "root//lib:a": {"buck.deps": ["root//lib:b"], "exported_deps": ["root//lib:b"]},
+            "root//lib:b": {"buck.deps": []}
+        }"#;
+        let paths = DepPathView::parse_paths(output, "root//app:main", "root//lib:b").unwrap();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(
+            hop_labels(&paths[0]),
+            vec!["root//app:main", "root//lib:a", "root//lib:b"]
+        );
+        assert!(paths[0].hops[0].via.is_empty());
+        assert_eq!(paths[0].hops[1].via, vec!["deps"]);
+        assert_eq!(paths[0].hops[2].via, vec!["exported_deps"]);
+    }
+
+    #[test]
+    fn lists_every_path_and_strips_configurations() {
+        let output = r#"{
+            "root//app:main (cfg//:linux)": {
+                "buck.deps": ["root//lib:a (cfg//:linux)", "root//lib:b (cfg//:linux)"]
+            },
+            "root//lib:a (cfg//:linux)": {"buck.deps": ["root//lib:b (cfg//:linux)"]},
+            "root//lib:b (cfg//:linux)": {"buck.deps": []}
+        }"#;
+        let paths = DepPathView::parse_paths(output, "root//app:main", "root//lib:b").unwrap();
+        let mut chains: Vec<Vec<&str>> = paths.iter().map(hop_labels).collect();
+        chains.sort();
+        assert_eq!(
+            chains,
+            vec![
+                vec!["root//app:main", "root//lib:a", "root//lib:b"],
+                vec!["root//app:main", "root//lib:b"],
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_result_means_no_path() {
+        let paths = DepPathView::parse_paths("{}", "root//app:main", "root//lib:b").unwrap();
+        assert!(paths.is_empty());
+    }
+
+    #[test]
+    fn missing_end_is_an_error() {
+        let output = r#"{"root//app:main": {"buck.deps": []}}"#;
+        let error = DepPathView::parse_paths(output, "root//app:main", "root//lib:b").unwrap_err();
+        assert_eq!(error.to_string(), "root//lib:b not in the result");
+    }
+
+    #[test]
+    fn names_every_attribute_referencing_the_dep() {
+        let attrs = serde_json::json!({
+            "buck.deps": ["root//lib:a"],
+            "deps": ["root//lib:a", "root//lib:c"],
+            "exported_deps": ["root//lib:a (cfg//:linux)"],
+            "platform_deps": [["linux", ["root//lib:a"]]],
+            "srcs": ["lib.rs"]
+        });
+        let attrs = attrs.as_object().unwrap();
+        assert_eq!(
+            attributes_referencing(attrs, "root//lib:a"),
+            vec!["deps", "exported_deps", "platform_deps"]
+        );
+        assert_eq!(attributes_referencing(attrs, "root//lib:c"), vec!["deps"]);
+    }
+}
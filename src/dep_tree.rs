@@ -0,0 +1,422 @@
+use anyhow::Result;
+use anyhow::anyhow;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use futures::FutureExt;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::debug;
+
+use crate::buck::BuckProject;
+use crate::buck::parse_query_labels;
+use crate::scheduler::Priority;
+use crate::scheduler::Scheduler;
+use crate::scheduler::Task;
+
+// (generation, label, deps) of finished deps queries
+type DepsResults = Arc<Mutex<Vec<(u64, String, Result<Vec<String>>)>>>;
+
+// Levels `e` expands below the selected node, each needing a `deps(x, 1)` query per node
+const EXPAND_ALL_DEPTH: usize = 3;
+// Expanding everything stops once the tree has this many nodes
+const MAX_EXPANDED_NODES: usize = 500;
+
+/// Direct dependencies of a label, loaded lazily
+#[derive(Debug, Clone)]
+pub enum DepsState {
+    Loading,
+    Loaded(Vec<String>),
+    Failed(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NodeMark {
+    None,
+    // The label is one of the node's ancestors, expanding it would loop forever
+    Cycle,
+    // The label already appears elsewhere in the tree
+    Seen,
+}
+
+#[derive(Debug, Clone)]
+pub struct DepNode {
+    pub label: String,
+    pub depth: usize,
+    pub mark: NodeMark,
+    pub expanded: bool,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    // Levels of children still to expand recursively as their deps arrive
+    expand_levels: usize,
+}
+
+/// Expandable transitive dependency tree of a target
+pub struct DepTree {
+    pub active: bool,
+    pub nodes: Vec<DepNode>,
+    pub selected: usize,
+    pub deps: HashMap<String, DepsState>,
+    // Transitive dependency count per label, and whether the count is complete
+    pub transitive_counts: HashMap<String, (usize, bool)>,
+    // Labels depending on each label, from the loaded deps
+    rdeps: HashMap<String, Vec<String>>,
+    // Labels whose deps arrived since the counts were last updated
+    newly_loaded: HashSet<String>,
+    results: DepsResults,
+    // Bumped when the cached deps are dropped, results of older queries are ignored
+    generation: u64,
+    current_dir: PathBuf,
+    // Platform the cached deps were resolved for, None for the unconfigured graph
+    platform: Option<String>,
+}
+
+impl DepTree {
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            nodes: Vec::new(),
+            selected: 0,
+            deps: HashMap::new(),
+            transitive_counts: HashMap::new(),
+            rdeps: HashMap::new(),
+            newly_loaded: HashSet::new(),
+            results: Arc::new(Mutex::new(Vec::new())),
+            generation: 0,
+            current_dir: PathBuf::new(),
+            platform: None,
+        }
+    }
+
+    /// Open the tree rooted at `label`. Loaded deps are kept between openings
+    /// as long as the target platform doesn't change.
+    pub fn open(&mut self, label: String, project: &BuckProject, scheduler: &Scheduler) {
+        self.active = true;
+        self.current_dir = project.root_path.clone();
+        if self.platform != project.target_platform {
+            self.platform = project.target_platform.clone();
+            self.deps.clear();
+            self.transitive_counts.clear();
+            self.rdeps.clear();
+            self.newly_loaded.clear();
+            self.generation += 1;
+        }
+        self.selected = 0;
+        self.nodes = vec![DepNode {
+            label,
+            depth: 0,
+            mark: NodeMark::None,
+            expanded: false,
+            parent: None,
+            children: Vec::new(),
+            expand_levels: 0,
+        }];
+        self.expand(0, project, scheduler);
+        self.update_transitive_counts();
+    }
+
+    pub fn close(&mut self) {
+        self.active = false;
+    }
+
+    /// Node indices in display order, skipping the children of collapsed nodes
+    pub fn visible_nodes(&self) -> Vec<usize> {
+        let mut visible = Vec::new();
+        if self.nodes.is_empty() {
+            return visible;
+        }
+
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            visible.push(index);
+            let node = &self.nodes[index];
+            if node.expanded {
+                stack.extend(node.children.iter().rev());
+            }
+        }
+        visible
+    }
+
+    /// Handle a key while the tree is open.
+    /// Returns the label the user wants to jump to, if any.
+    pub fn handle_key(
+        &mut self,
+        key: KeyEvent,
+        project: &BuckProject,
+        scheduler: &Scheduler,
+    ) -> Option<String> {
+        let visible = self.visible_nodes();
+        let Some(&current) = visible.get(self.selected) else {
+            if key.code == KeyCode::Esc {
+                self.close();
+            }
+            return None;
+        };
+
+        match key.code {
+            KeyCode::Esc => self.close(),
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.selected = (self.selected + 1) % visible.len();
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.selected = (self.selected + visible.len() - 1) % visible.len();
+            }
+            KeyCode::Char('l') | KeyCode::Right | KeyCode::Char(' ') => {
+                if self.nodes[current].expanded {
+                    self.nodes[current].expanded = false;
+                } else {
+                    self.expand(current, project, scheduler);
+                    self.update_transitive_counts();
+                }
+            }
+            KeyCode::Char('h') | KeyCode::Left => {
+                if self.nodes[current].expanded {
+                    self.nodes[current].expanded = false;
+                } else if let Some(parent) = self.nodes[current].parent {
+                    // Move to the parent and fold it
+                    self.nodes[parent].expanded = false;
+                    self.selected = self
+                        .visible_nodes()
+                        .iter()
+                        .position(|&index| index == parent)
+                        .unwrap_or(0);
+                }
+            }
+            KeyCode::Char('e') => {
+                self.nodes[current].expand_levels = EXPAND_ALL_DEPTH;
+                self.expand(current, project, scheduler);
+                self.update_transitive_counts();
+            }
+            KeyCode::Enter => return Some(self.nodes[current].label.clone()),
+            _ => {}
+        }
+        None
+    }
+
+    fn expand(&mut self, index: usize, project: &BuckProject, scheduler: &Scheduler) {
+        if self.nodes[index].mark == NodeMark::Cycle {
+            return;
+        }
+        self.nodes[index].expanded = true;
+
+        let label = self.nodes[index].label.clone();
+        if !self.deps.contains_key(&label) {
+            // Deps from `buck2 targets -A` are unconfigured, only reuse them without a platform
+            let loaded = project
+                .find_loaded_target(&label)
+                .filter(|_| project.target_platform.is_none());
+            if let Some(target) = loaded {
+                self.set_deps(&label, DepsState::Loaded(target.deps.clone()));
+            } else {
+                self.request_deps(&label, project, scheduler);
+            }
+        }
+
+        self.populate_children(index, project, scheduler);
+    }
+
+    fn request_deps(&mut self, label: &str, project: &BuckProject, scheduler: &Scheduler) {
+        debug!("Loading deps of {}", label);
+        self.deps.insert(label.to_string(), DepsState::Loading);
+
+        let task = Self::create_deps_loading_task(
+            self.generation,
+            label.to_string(),
+            project.query_command(format!("deps({}, 1)", label)),
+            self.current_dir.clone(),
+            self.results.clone(),
+        );
+        scheduler.dispatch_micro(task);
+    }
+
+    fn create_deps_loading_task(
+        generation: u64,
+        label: String,
+        cmds: Vec<String>,
+        current_dir: PathBuf,
+        results: DepsResults,
+    ) -> Task {
+        let failure_results = results.clone();
+        let success_label = label.clone();
+        let failure_label = label.clone();
+
+        let task_on_success = Box::new(move |output: String| {
+            async move {
+                // deps(x, 1) includes x itself
+                let deps = parse_query_labels(&output)
+                    .into_iter()
+                    .filter(|dep| *dep != success_label)
+                    .collect();
+                results
+                    .lock()
+                    .await
+                    .push((generation, success_label, Ok(deps)));
+            }
+            .boxed()
+        });
+
+        let task_on_failure = Box::new(move |stderr: String| {
+            async move {
+                failure_results.lock().await.push((
+                    generation,
+                    failure_label,
+                    Err(anyhow!(stderr.trim().to_string())),
+                ));
+            }
+            .boxed()
+        });
+
+        Task::new(Priority::Normal, cmds, current_dir, task_on_success)
+            .with_on_failure(task_on_failure)
+    }
+
+    /// Create the child nodes of an expanded node once its deps are known
+    fn populate_children(&mut self, index: usize, project: &BuckProject, scheduler: &Scheduler) {
+        if !self.nodes[index].children.is_empty() {
+            return;
+        }
+        let Some(DepsState::Loaded(deps)) = self.deps.get(&self.nodes[index].label) else {
+            return;
+        };
+        let deps = deps.clone();
+
+        let mut ancestors = HashSet::new();
+        let mut cursor = Some(index);
+        while let Some(i) = cursor {
+            ancestors.insert(self.nodes[i].label.clone());
+            cursor = self.nodes[i].parent;
+        }
+        let existing: HashSet<String> = self.nodes.iter().map(|n| n.label.clone()).collect();
+
+        let depth = self.nodes[index].depth + 1;
+        let expand_levels = self.nodes[index].expand_levels;
+        let mut to_expand = Vec::new();
+
+        for dep in deps {
+            let mark = if ancestors.contains(&dep) {
+                NodeMark::Cycle
+            } else if existing.contains(&dep) {
+                NodeMark::Seen
+            } else {
+                NodeMark::None
+            };
+
+            let child = self.nodes.len();
+            self.nodes.push(DepNode {
+                label: dep,
+                depth,
+                mark,
+                expanded: false,
+                parent: Some(index),
+                children: Vec::new(),
+                expand_levels: expand_levels.saturating_sub(1),
+            });
+            self.nodes[index].children.push(child);
+
+            if expand_levels > 0 && mark == NodeMark::None {
+                to_expand.push(child);
+            }
+        }
+
+        for child in to_expand {
+            if self.nodes.len() >= MAX_EXPANDED_NODES {
+                break;
+            }
+            self.expand(child, project, scheduler);
+        }
+    }
+
+    /// Pick up loaded deps, called from the UI loop
+    pub async fn update_results(&mut self, project: &BuckProject, scheduler: &Scheduler) {
+        let results = {
+            let mut results = self.results.lock().await;
+            std::mem::take(&mut *results)
+        };
+        if results.is_empty() {
+            return;
+        }
+
+        for (generation, label, result) in results {
+            // Started under another platform, the deps no longer apply
+            if generation != self.generation {
+                continue;
+            }
+            let state = match result {
+                Ok(deps) => DepsState::Loaded(deps),
+                Err(e) => DepsState::Failed(e.to_string()),
+            };
+            self.set_deps(&label, state);
+
+            // Fill in every expanded node waiting for this label
+            let waiting: Vec<usize> = (0..self.nodes.len())
+                .filter(|&i| self.nodes[i].label == label && self.nodes[i].expanded)
+                .collect();
+            for index in waiting {
+                self.populate_children(index, project, scheduler);
+            }
+        }
+
+        self.update_transitive_counts();
+    }
+
+    fn set_deps(&mut self, label: &str, state: DepsState) {
+        if let DepsState::Loaded(deps) = &state {
+            for dep in deps {
+                self.rdeps
+                    .entry(dep.clone())
+                    .or_default()
+                    .push(label.to_string());
+            }
+        }
+        self.deps.insert(label.to_string(), state);
+        self.newly_loaded.insert(label.to_string());
+    }
+
+    /// Count the unique transitive deps of the labels in the tree from the loaded deps.
+    /// A count is incomplete while some of the reachable deps are not loaded yet. Only the
+    /// labels without a count and the ones depending on newly loaded deps are counted again.
+    fn update_transitive_counts(&mut self) {
+        let mut affected: HashSet<String> = HashSet::new();
+        let mut stack: Vec<String> = self.newly_loaded.drain().collect();
+        while let Some(label) = stack.pop() {
+            if let Some(rdeps) = self.rdeps.get(&label) {
+                stack.extend(rdeps.iter().filter(|r| !affected.contains(*r)).cloned());
+            }
+            affected.insert(label);
+        }
+
+        let labels: HashSet<&String> = self
+            .nodes
+            .iter()
+            .map(|n| &n.label)
+            .filter(|label| match self.transitive_counts.get(*label) {
+                Some((_, complete)) => !complete && affected.contains(*label),
+                None => true,
+            })
+            .collect();
+
+        for label in labels {
+            let mut seen = HashSet::new();
+            let mut complete = true;
+            let mut stack = vec![label.as_str()];
+
+            while let Some(current) = stack.pop() {
+                match self.deps.get(current) {
+                    Some(DepsState::Loaded(deps)) => {
+                        for dep in deps {
+                            if dep != label && seen.insert(dep.as_str()) {
+                                stack.push(dep);
+                            }
+                        }
+                    }
+                    _ => complete = false,
+                }
+            }
+
+            self.transitive_counts
+                .insert(label.clone(), (seen.len(), complete));
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::process::Stdio;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// Overrides the built-in line jump template, e.g. `+{line} {file}`
+const TEMPLATE_ENV: &str = "BUCK_TUI_EDITOR_TEMPLATE";
+
+/// A file to open, queued by the views and task callbacks and launched from the UI loop,
+/// which can suspend the terminal for editors running inside it
+#[derive(Debug, Clone)]
+pub struct EditorRequest {
+    pub file: PathBuf,
+    pub line: Option<u32>,
+    // Target whose definition this is, recorded as opened once the editor launched
+    pub label: Option<String>,
+}
+
+pub type EditorRequests = Arc<Mutex<Vec<EditorRequest>>>;
+
+/// Editor from `$VISUAL`/`$EDITOR` with the arguments it takes to jump to a line
+#[derive(Debug, Clone)]
+pub struct Editor {
+    program: String,
+    args: Vec<String>,
+    template: String,
+    terminal: bool,
+}
+
+impl Editor {
+    /// `$VISUAL`, then `$EDITOR`, then VS Code if installed, then vi
+    pub fn from_env() -> Self {
+        let command = ["VISUAL", "EDITOR"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.trim().is_empty())
+            .unwrap_or_else(|| {
+                if which::which("code").is_ok() {
+                    "code".to_string()
+                } else {
+                    "vi".to_string()
+                }
+            });
+        Self::parse(&command, std::env::var(TEMPLATE_ENV).ok())
+    }
+
+    /// Editor from a `$EDITOR`-style command line, `custom_template` replacing the line jump
+    /// template of known editors
+    fn parse(command: &str, custom_template: Option<String>) -> Self {
+        let mut parts = command.split_whitespace().map(|s| s.to_string());
+        let program = parts.next().unwrap_or_else(|| "vi".to_string());
+        let args: Vec<String> = parts.collect();
+
+        let name = Path::new(&program)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let (template, terminal) = Self::known_editor(&name, &args);
+        let template = custom_template
+            .filter(|t| t.contains("{file}"))
+            .unwrap_or_else(|| template.to_string());
+
+        Self {
+            program,
+            args,
+            template,
+            terminal,
+        }
+    }
+
+    /// Line jump template and whether the editor runs inside the terminal
+    fn known_editor(name: &str, args: &[String]) -> (&'static str, bool) {
+        match name {
+            "vi" | "vim" | "nvim" | "nano" | "micro" | "kak" => ("+{line} {file}", true),
+            "emacs" | "emacsclient" => {
+                let in_terminal = args
+                    .iter()
+                    .any(|a| matches!(a.as_str(), "-nw" | "-t" | "--tty"));
+                ("+{line} {file}", in_terminal)
+            }
+            "hx" | "helix" => ("{file}:{line}", true),
+            "code" | "code-insiders" | "codium" | "cursor" => ("-g {file}:{line}", false),
+            "subl" | "zed" | "mate" => ("{file}:{line}", false),
+            "idea" | "clion" | "pycharm" | "goland" => ("--line {line} {file}", false),
+            "gvim" | "mvim" => ("+{line} {file}", false),
+            _ => ("{file}", true),
+        }
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        self.terminal
+    }
+
+    pub fn program(&self) -> &str {
+        &self.program
+    }
+
+    /// Command opening `file`, at `line` when given
+    pub fn command(&self, file: &Path, line: Option<u32>) -> Command {
+        let file = file.display().to_string();
+        let mut command = Command::new(&self.program);
+        command.args(&self.args);
+
+        match line {
+            Some(line) => {
+                for arg in self.template.split_whitespace() {
+                    command.arg(
+                        arg.replace("{file}", &file)
+                            .replace("{line}", &line.to_string()),
+                    );
+                }
+            }
+            None => {
+                command.arg(file);
+            }
+        }
+
+        if !self.terminal {
+            // GUI editors outlive the TUI, keep them off its terminal
+            command
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null());
+        }
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(editor: &Editor, line: Option<u32>) -> Vec<String> {
+        editor
+            .command(Path::new("/repo/pkg/BUCK"), line)
+            .get_args()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn known_editors_jump_to_the_line() {
+        let cases = [
+            ("vim", vec!["+12", "/repo/pkg/BUCK"], true),
+            ("/usr/bin/nvim", vec!["+12", "/repo/pkg/BUCK"], true),
+            ("hx", vec!["/repo/pkg/BUCK:12"], true),
+            ("code", vec!["-g", "/repo/pkg/BUCK:12"], false),
+            ("zed", vec!["/repo/pkg/BUCK:12"], false),
+            ("clion", vec!["--line", "12", "/repo/pkg/BUCK"], false),
+            ("gvim", vec!["+12", "/repo/pkg/BUCK"], false),
+            ("emacs", vec!["+12", "/repo/pkg/BUCK"], false),
+        ];
+        for (command, expected, terminal) in cases {
+            let editor = Editor::parse(command, None);
+            assert_eq!(args(&editor, Some(12)), expected, "{}", command);
+            assert_eq!(editor.is_terminal(), terminal, "{}", command);
+        }
+    }
+
+    #[test]
+    fn without_a_line_only_the_file_is_passed() {
+        let editor = Editor::parse("code", None);
+        assert_eq!(args(&editor, None), vec!["/repo/pkg/BUCK"]);
+    }
+
+    #[test]
+    fn custom_editor_keeps_its_arguments() {
+        let editor = Editor::parse("code --wait --new-window", None);
+        assert_eq!(editor.program(), "code");
+        assert_eq!(
+            args(&editor, Some(3)),
+            vec!["--wait", "--new-window", "-g", "/repo/pkg/BUCK:3"]
+        );
+
+        let editor = Editor::parse("emacsclient -nw", None);
+        assert!(editor.is_terminal());
+        assert_eq!(args(&editor, Some(3)), vec!["-nw", "+3", "/repo/pkg/BUCK"]);
+    }
+
+    #[test]
+    fn unknown_editor_runs_in_the_terminal_without_line() {
+        let editor = Editor::parse("my-editor --flag", None);
+        assert!(editor.is_terminal());
+        assert_eq!(args(&editor, Some(3)), vec!["--flag", "/repo/pkg/BUCK"]);
+    }
+
+    #[test]
+    fn template_override_needs_the_file() {
+        let editor = Editor::parse("my-editor", Some("--goto {file}:{line}".to_string()));
+        assert_eq!(args(&editor, Some(7)), vec!["--goto", "/repo/pkg/BUCK:7"]);
+
+        let editor = Editor::parse("vim", Some("--line {line}".to_string()));
+        assert_eq!(args(&editor, Some(7)), vec!["+7", "/repo/pkg/BUCK"]);
+    }
+}
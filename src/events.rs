@@ -3,8 +3,15 @@ use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 
 use crate::app::SearchState;
+use crate::attributes::AttributeRow;
+use crate::attributes::attribute_rows;
+use crate::attributes::label_entries;
 use crate::buck::BuckProject;
+use crate::filter::TargetFilter;
+use crate::recent::ActionResult;
+use crate::recent::RecentAction;
 use crate::scheduler::Scheduler;
+use crate::ui::DetailsTab;
 use crate::ui::Pane;
 use crate::ui::PaneGroup;
 use crate::ui::UI;
@@ -154,13 +161,14 @@ impl EventHandler {
                     .collect()
             }
             crate::app::SearchPane::Targets => {
-                // Search in targets list
+                // Search in targets list, the query may contain field predicates like `rule:`
+                let filter = TargetFilter::parse(&search_state.query);
                 project
                     .filtered_targets
                     .iter()
                     .enumerate()
                     .filter_map(|(idx, target)| {
-                        if target.display_title().to_lowercase().contains(&query_lower) {
+                        if filter.matches(target) {
                             Some(idx)
                         } else {
                             None
@@ -288,24 +296,81 @@ impl EventHandler {
                 }
             }
             KeyCode::Char('n') if search_state.total_matches > 0 => {
+                project.record_jump();
                 search_state.next_match();
                 self.navigate_to_current_match(project, ui, search_state, scheduler);
             }
             KeyCode::Char('N') if search_state.total_matches > 0 => {
+                project.record_jump();
                 search_state.prev_match();
                 self.navigate_to_current_match(project, ui, search_state, scheduler);
             }
+            KeyCode::Char('f') if ui.current_pane == Pane::Targets => {
+                if project.target_filter.is_some() {
+                    project.set_target_filter(None);
+                    ui.show_info("Filter cleared");
+                } else if !search_state.query.is_empty()
+                    && search_state.searching_in_pane == crate::app::SearchPane::Targets
+                {
+                    project.set_target_filter(Some(search_state.query.clone()));
+                    ui.show_info(format!("Filtered to {}", search_state.query));
+                } else {
+                    ui.show_info("Search the targets with / first, f then filters them");
+                }
+                self.update_search_matches(project, ui, search_state, project.selected_target);
+            }
             KeyCode::Char('a') => {
-                if ui.current_pane == Pane::Targets && project.get_selected_target().is_some() {
+                if ui.current_pane == Pane::Targets && !project.action_labels().is_empty() {
                     *show_actions = true;
                     *selected_action = 0;
                 }
             }
+            KeyCode::Char(' ') if ui.current_pane == Pane::Targets => {
+                project.toggle_target_selection();
+                project.next_target(scheduler);
+            }
+            KeyCode::Char('v') if ui.current_pane == Pane::Targets => {
+                if project.toggle_visual_range() {
+                    ui.show_info("Visual range: move to extend, v to add it to the selection");
+                }
+            }
+            KeyCode::Char('A') if ui.current_pane == Pane::Targets => {
+                project.toggle_package_selection();
+            }
+            KeyCode::Char(' ')
+                if ui.current_pane == Pane::Details && ui.details_tab == DetailsTab::Info =>
+            {
+                if let Some((labels, rows)) = Self::details_entries(project, ui) {
+                    ui.attribute_tree.toggle(&rows, labels.len());
+                }
+            }
             KeyCode::Char('o') => {
-                if ui.current_pane == Pane::Targets {
+                let load_error = project
+                    .get_selected_directory()
+                    .and_then(|dir| dir.load_error.clone());
+                if ui.current_pane == Pane::Targets
+                    && let Some(error) = load_error
+                {
+                    // Nothing to open a definition of, go to the line that broke evaluation
+                    match error.file {
+                        Some(file) => project.open_in_editor(file, error.line).await,
+                        None => ui.show_error(error.message),
+                    }
+                } else if ui.current_pane == Pane::Targets {
                     project.open_target_definition(scheduler);
+                } else if ui.current_pane == Pane::Details
+                    && ui.details_tab == DetailsTab::Sources
+                    && let Some(file) = ui.sources.selected()
+                {
+                    project.open_in_editor(file.path.clone(), None).await;
                 }
             }
+            KeyCode::Char('s') if ui.current_group == PaneGroup::Inspector => {
+                ui.details_tab = match ui.details_tab {
+                    DetailsTab::Info => DetailsTab::Sources,
+                    DetailsTab::Sources => DetailsTab::Info,
+                };
+            }
             KeyCode::Tab => {
                 // Switch between Explorer and Inspector groups
                 ui.current_group = match ui.current_group {
@@ -393,7 +458,12 @@ impl EventHandler {
                         // For now, no navigation within selected directory
                     }
                     Pane::Targets => project.next_target(scheduler),
-                    Pane::Details => {}
+                    Pane::Details if ui.details_tab == DetailsTab::Sources => ui.sources.next(),
+                    Pane::Details => {
+                        if let Some((labels, rows)) = Self::details_entries(project, ui) {
+                            ui.attribute_tree.next(labels.len() + rows.len());
+                        }
+                    }
                 }
             }
             KeyCode::Char('k') | KeyCode::Up => {
@@ -421,7 +491,12 @@ impl EventHandler {
                         ui.current_pane = Pane::Targets;
                     }
                     Pane::Targets => project.prev_target(scheduler),
-                    Pane::Details => {}
+                    Pane::Details if ui.details_tab == DetailsTab::Sources => ui.sources.prev(),
+                    Pane::Details => {
+                        if let Some((labels, rows)) = Self::details_entries(project, ui) {
+                            ui.attribute_tree.prev(labels.len() + rows.len());
+                        }
+                    }
                 }
             }
             KeyCode::Enter => {
@@ -450,8 +525,26 @@ impl EventHandler {
                     }
                     Pane::Targets => {
                         ui.current_pane = Pane::Details;
+                        if let Some(target) = project.get_selected_target() {
+                            let label = target.full_target_label_name.clone();
+                            project.recent.record(
+                                &label,
+                                RecentAction::Inspected,
+                                ActionResult::Done,
+                            );
+                        }
+                    }
+                    Pane::Details if ui.details_tab == DetailsTab::Sources => {}
+                    Pane::Details => {
+                        // Follow the dep or visibility label under the cursor, or fold or
+                        // unfold the attribute under it
+                        if let Some((labels, rows)) = Self::details_entries(project, ui) {
+                            match ui.attribute_tree.selected_label(&labels) {
+                                Some(label) => Self::follow_label(label, project, ui, scheduler),
+                                None => ui.attribute_tree.toggle(&rows, labels.len()),
+                            }
+                        }
                     }
-                    Pane::Details => {}
                 }
             }
             _ => {}
@@ -459,12 +552,48 @@ impl EventHandler {
         Ok(())
     }
 
+    /// Label entries and attribute rows the Details cursor moves over, for the selected target
+    fn details_entries(
+        project: &BuckProject,
+        ui: &mut UI,
+    ) -> Option<(Vec<String>, Vec<AttributeRow>)> {
+        let target = project.get_selected_target()?;
+        ui.attribute_tree.sync(&target.full_target_label_name);
+        let labels = label_entries(&target.visibility, project.displayed_deps(target));
+        let rows = attribute_rows(&target.attributes, &ui.attribute_tree.expanded);
+        Some((labels, rows))
+    }
+
+    /// Navigate to a dep or visibility entry of the Details pane. Patterns like `//foo/...`
+    /// go to the package they are rooted at.
+    fn follow_label(label: &str, project: &mut BuckProject, ui: &mut UI, scheduler: &Scheduler) {
+        let pattern = label
+            .trim_end_matches("...")
+            .trim_end_matches('/')
+            .trim_end_matches(':');
+        let result = if pattern == "PUBLIC" {
+            Err(anyhow::anyhow!("PUBLIC is visible to every target"))
+        } else {
+            project
+                .absolute_label(pattern)
+                .and_then(|label| project.navigate_to_label(&label, scheduler))
+        };
+
+        match result {
+            Ok(()) => {
+                ui.current_group = PaneGroup::Inspector;
+                ui.current_pane = Pane::Targets;
+            }
+            Err(e) => ui.show_error(format!("Cannot go to {}: {}", label, e)),
+        }
+    }
+
     async fn handle_actions_mode(
         &mut self,
         key: KeyEvent,
         project: &mut BuckProject,
         _ui: &mut UI,
-        _scheduler: &Scheduler,
+        scheduler: &Scheduler,
         show_actions: &mut bool,
         selected_action: &mut usize,
     ) -> Result<()> {
@@ -482,20 +611,13 @@ impl EventHandler {
                 *selected_action = (*selected_action + action_count - 1) % action_count;
             }
             KeyCode::Enter => {
-                if let Some(target) = project.get_selected_target() {
-                    let target_name = &target.full_target_label_name;
-                    match *selected_action {
-                        0 => {
-                            debug!("Building target: {}", target_name);
-                            // TODO: Execute build command via scheduler
-                        }
-                        1 => {
-                            debug!("Testing target: {}", target_name);
-                            // TODO: Execute test command via scheduler
-                        }
-                        _ => {}
-                    }
-                }
+                let labels = project.action_labels();
+                let action = match *selected_action {
+                    0 => RecentAction::Built,
+                    _ => RecentAction::Tested,
+                };
+                debug!("Running {} on {}", action.name(), labels.join(" "));
+                project.run_target_action(labels, action, scheduler);
                 *show_actions = false;
                 *selected_action = 0;
             }
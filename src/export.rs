@@ -0,0 +1,558 @@
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::info;
+
+use crate::buck::BuckProject;
+use crate::buck::CommandOutput;
+use crate::buck::create_command_task;
+use crate::buck::split_configured_label;
+use crate::scheduler::Scheduler;
+use crate::scheduler::TaskId;
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum ExportFormat {
+    Dot,
+    Mermaid,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportFormat::Dot => "Graphviz DOT",
+            ExportFormat::Mermaid => "Mermaid",
+            ExportFormat::Json => "JSON adjacency list",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Dot => "dot",
+            ExportFormat::Mermaid => "mmd",
+            ExportFormat::Json => "json",
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            ExportFormat::Dot => ExportFormat::Mermaid,
+            ExportFormat::Mermaid => ExportFormat::Json,
+            ExportFormat::Json => ExportFormat::Dot,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphNode {
+    pub rule_type: String,
+    pub oncall: Option<String>,
+    pub deps: Vec<String>,
+}
+
+/// Dependency graph keyed by target label, edges limited to the exported nodes
+#[derive(Debug, Clone, Serialize)]
+pub struct DepGraph {
+    pub nodes: BTreeMap<String, GraphNode>,
+}
+
+impl DepGraph {
+    /// Build the graph from `buck2 uquery "deps(...)" --output-all-attributes` JSON.
+    /// cquery labels are keyed without their configuration.
+    pub fn from_query_output(output: &str) -> Result<Self> {
+        let json: serde_json::Map<String, serde_json::Value> = serde_json::from_str(output)
+            .map_err(|e| anyhow!("Unexpected buck2 query output: {}", e))?;
+        let labels: HashSet<&str> = json
+            .keys()
+            .map(|label| split_configured_label(label).0)
+            .collect();
+
+        let mut nodes = BTreeMap::new();
+        for (label, attrs) in &json {
+            let rule_type = attrs
+                .get("buck.type")
+                .and_then(|v| v.as_str())
+                .map(|s| s.split(':').next_back().unwrap_or("unknown"))
+                .unwrap_or("unknown")
+                .to_string();
+
+            let oncall = attrs
+                .get("buck.oncall")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+
+            // Deps beyond the depth limit are not part of the graph
+            let deps = attrs
+                .get("buck.deps")
+                .and_then(|v| v.as_array())
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|v| v.as_str())
+                        .map(|dep| split_configured_label(dep).0)
+                        .filter(|dep| labels.contains(dep))
+                        .map(|s| s.to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            nodes.insert(
+                split_configured_label(label).0.to_string(),
+                GraphNode {
+                    rule_type,
+                    oncall,
+                    deps,
+                },
+            );
+        }
+
+        Ok(Self { nodes })
+    }
+
+    pub fn render(&self, format: ExportFormat) -> Result<String> {
+        match format {
+            ExportFormat::Dot => Ok(self.to_dot()),
+            ExportFormat::Mermaid => Ok(self.to_mermaid()),
+            ExportFormat::Json => Ok(serde_json::to_string_pretty(&self.nodes)?),
+        }
+    }
+
+    fn to_dot(&self) -> String {
+        let mut out = String::from("digraph deps {\n    node [shape=box];\n");
+        for (label, node) in &self.nodes {
+            // `\n` is a line break in DOT, the parts are escaped on their own
+            let mut text = format!("{}\\n{}", escape_dot(label), escape_dot(&node.rule_type));
+            if let Some(oncall) = &node.oncall {
+                text.push_str(&format!("\\noncall: {}", escape_dot(oncall)));
+            }
+            out.push_str(&format!(
+                "    \"{}\" [label=\"{}\"];\n",
+                escape_dot(label),
+                text
+            ));
+        }
+        for (label, node) in &self.nodes {
+            for dep in &node.deps {
+                out.push_str(&format!(
+                    "    \"{}\" -> \"{}\";\n",
+                    escape_dot(label),
+                    escape_dot(dep)
+                ));
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    fn to_mermaid(&self) -> String {
+        // Mermaid ids can't contain label characters, number the nodes instead
+        let ids: BTreeMap<&str, String> = self
+            .nodes
+            .keys()
+            .enumerate()
+            .map(|(i, label)| (label.as_str(), format!("n{}", i)))
+            .collect();
+
+        let mut out = String::from("graph LR\n");
+        for (label, node) in &self.nodes {
+            let mut text = format!(
+                "{}<br/>{}",
+                escape_mermaid(label),
+                escape_mermaid(&node.rule_type)
+            );
+            if let Some(oncall) = &node.oncall {
+                text.push_str(&format!("<br/>oncall: {}", escape_mermaid(oncall)));
+            }
+            out.push_str(&format!("    {}[\"{}\"]\n", ids[label.as_str()], text));
+        }
+        for (label, node) in &self.nodes {
+            for dep in &node.deps {
+                out.push_str(&format!(
+                    "    {} --> {}\n",
+                    ids[label.as_str()],
+                    ids[dep.as_str()]
+                ));
+            }
+        }
+        out
+    }
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid(s: &str) -> String {
+    s.replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
+
+/// `deps()` expression for `roots`, a depth of 0 means unlimited
+fn deps_query(roots: &str, depth: u32) -> String {
+    if depth == 0 {
+        format!("deps({})", roots)
+    } else {
+        format!("deps({}, {})", roots, depth)
+    }
+}
+
+/// The `buck2 uquery`/`buck2 cquery` deps command `cmds` with the attributes the graph is
+/// built from
+fn export_command(mut cmds: Vec<String>) -> Vec<String> {
+    cmds.push("--output-all-attributes".to_owned());
+    cmds
+}
+
+/// Export without the TUI, for `--export`. Writes to stdout when no output file is given.
+pub fn run_headless(
+    project_path: &Path,
+    roots: &str,
+    format: ExportFormat,
+    depth: u32,
+    output_file: Option<&Path>,
+) -> Result<()> {
+    // There is no target platform outside the TUI, the headless export is unconfigured
+    let cmds = export_command(vec![
+        "buck2".to_owned(),
+        "uquery".to_owned(),
+        deps_query(roots, depth),
+    ]);
+    let output = Command::new(&cmds[0])
+        .args(&cmds[1..])
+        .current_dir(project_path)
+        .stdin(std::process::Stdio::null())
+        .output()
+        .context("Failed to run buck2")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "buck2 uquery failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let graph = DepGraph::from_query_output(&String::from_utf8_lossy(&output.stdout))?;
+    let rendered = graph.render(format)?;
+
+    match output_file {
+        Some(path) => std::fs::write(path, rendered)
+            .with_context(|| format!("Failed to write {}", path.display()))?,
+        None => print!("{}", rendered),
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportScope {
+    // The selected target and its deps
+    Target,
+    // Every target of the selected package and their deps
+    Package,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportField {
+    Scope,
+    Format,
+    Depth,
+    Path,
+}
+
+impl ExportField {
+    const ALL: [ExportField; 4] = [
+        ExportField::Scope,
+        ExportField::Format,
+        ExportField::Depth,
+        ExportField::Path,
+    ];
+}
+
+/// Dialog exporting the dependency graph of the selected target or package to a file
+pub struct ExportDialog {
+    pub active: bool,
+    pub field: ExportField,
+    pub scope: ExportScope,
+    pub format: ExportFormat,
+    pub depth: u32,
+    pub path: String,
+    pub running: bool,
+    target: Option<String>,
+    package: Option<String>,
+    // The path was typed by the user, don't replace it with the default name anymore
+    path_edited: bool,
+    current_dir: PathBuf,
+    output: CommandOutput,
+    task_id: Option<TaskId>,
+    export_path: PathBuf,
+}
+
+impl ExportDialog {
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            field: ExportField::Scope,
+            scope: ExportScope::Target,
+            format: ExportFormat::Dot,
+            depth: 2,
+            path: String::new(),
+            running: false,
+            target: None,
+            package: None,
+            path_edited: false,
+            current_dir: PathBuf::new(),
+            output: Arc::new(Mutex::new(None)),
+            task_id: None,
+            export_path: PathBuf::new(),
+        }
+    }
+
+    pub fn open(&mut self, project: &BuckProject) {
+        self.active = true;
+        self.field = ExportField::Scope;
+        self.current_dir = project.current_path.clone();
+        self.target = project
+            .get_selected_target()
+            .map(|t| t.full_target_label_name.clone());
+        self.package = project.get_selected_buck_package_name();
+        if self.target.is_none() {
+            self.scope = ExportScope::Package;
+        }
+        self.path_edited = false;
+        self.update_default_path();
+    }
+
+    pub fn close(&mut self) {
+        self.active = false;
+    }
+
+    /// Target pattern of the exported roots
+    pub fn roots(&self) -> Option<String> {
+        match self.scope {
+            ExportScope::Target => self.target.clone(),
+            ExportScope::Package => self.package.as_ref().map(|p| format!("{}:", p)),
+        }
+    }
+
+    fn update_default_path(&mut self) {
+        if self.path_edited {
+            return;
+        }
+        let name = self
+            .roots()
+            .map(|roots| {
+                roots
+                    .trim_end_matches(':')
+                    .rsplit(['/', ':'])
+                    .find(|s| !s.is_empty())
+                    .unwrap_or("deps")
+                    .to_string()
+            })
+            .unwrap_or_else(|| "deps".to_string());
+        self.path = format!("{}-deps.{}", name, self.format.extension());
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent, project: &BuckProject, scheduler: &Scheduler) {
+        match key.code {
+            KeyCode::Esc => self.close(),
+            KeyCode::Enter => self.run(project, scheduler),
+            KeyCode::Down | KeyCode::Tab => {
+                let index = ExportField::ALL
+                    .iter()
+                    .position(|f| *f == self.field)
+                    .unwrap_or(0);
+                self.field = ExportField::ALL[(index + 1) % ExportField::ALL.len()];
+            }
+            KeyCode::Up => {
+                let index = ExportField::ALL
+                    .iter()
+                    .position(|f| *f == self.field)
+                    .unwrap_or(0);
+                self.field =
+                    ExportField::ALL[(index + ExportField::ALL.len() - 1) % ExportField::ALL.len()];
+            }
+            _ if self.field == ExportField::Path => match key.code {
+                KeyCode::Backspace => {
+                    self.path.pop();
+                    self.path_edited = true;
+                }
+                KeyCode::Char(c) => {
+                    self.path.push(c);
+                    self.path_edited = true;
+                }
+                _ => {}
+            },
+            KeyCode::Char('j') => {
+                self.handle_key(KeyEvent::from(KeyCode::Down), project, scheduler)
+            }
+            KeyCode::Char('k') => self.handle_key(KeyEvent::from(KeyCode::Up), project, scheduler),
+            KeyCode::Char('l') | KeyCode::Right | KeyCode::Char(' ') => self.change_field(true),
+            KeyCode::Char('h') | KeyCode::Left => self.change_field(false),
+            _ => {}
+        }
+    }
+
+    fn change_field(&mut self, forward: bool) {
+        match self.field {
+            ExportField::Scope => {
+                self.scope = match self.scope {
+                    ExportScope::Target => ExportScope::Package,
+                    ExportScope::Package if self.target.is_some() => ExportScope::Target,
+                    ExportScope::Package => ExportScope::Package,
+                };
+            }
+            ExportField::Format => {
+                self.format = if forward {
+                    self.format.next()
+                } else {
+                    self.format.next().next()
+                };
+            }
+            ExportField::Depth => {
+                self.depth = if forward {
+                    self.depth + 1
+                } else {
+                    self.depth.saturating_sub(1)
+                };
+            }
+            ExportField::Path => {}
+        }
+        self.update_default_path();
+    }
+
+    fn run(&mut self, project: &BuckProject, scheduler: &Scheduler) {
+        let Some(roots) = self.roots() else {
+            return;
+        };
+        if self.path.trim().is_empty() {
+            return;
+        }
+
+        if let Some(task_id) = self.task_id.take() {
+            scheduler.cancel(task_id);
+        }
+
+        let task = create_command_task(
+            export_command(project.query_command(deps_query(&roots, self.depth))),
+            self.current_dir.clone(),
+            self.output.clone(),
+        );
+        self.task_id = Some(task.id);
+        scheduler.dispatch_micro(task);
+
+        self.export_path = self.current_dir.join(self.path.trim());
+        self.running = true;
+    }
+
+    /// Write the graph once the query finished, called from the UI loop.
+    /// Returns the outcome to report in the status bar.
+    pub async fn update_results(&mut self) -> Option<Result<String>> {
+        let result = self.output.lock().await.take()?;
+        self.running = false;
+        self.task_id = None;
+
+        let written = result.and_then(|output| {
+            let graph = DepGraph::from_query_output(&output)?;
+            let rendered = graph.render(self.format)?;
+            std::fs::write(&self.export_path, rendered)
+                .with_context(|| format!("Failed to write {}", self.export_path.display()))?;
+            Ok(graph.nodes.len())
+        });
+
+        Some(written.map(|nodes| {
+            info!("Exported {} nodes to {:?}", nodes, self.export_path);
+            self.close();
+            format!(
+                "Exported {} nodes as {} to {}",
+                nodes,
+                self.format.name(),
+                self.export_path.display()
+            )
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_deps_beyond_the_depth_limit() {
+        // deps(root//app:main, 1) lists :lib but not its own deps
+        let output = r#"{
+            "root//app:main": {"buck.type": "prelude//rules.bzl:cxx_binary", "buck.deps": ["root//lib:lib"]},
+            "root//lib:lib": {"buck.type": "prelude//rules.bzl:cxx_library", "buck.deps": ["root//third_party:zlib"]}
+        }"#;
+        let graph = DepGraph::from_query_output(output).unwrap();
+        assert_eq!(graph.nodes["root//app:main"].deps, vec!["root//lib:lib"]);
+        assert_eq!(graph.nodes["root//app:main"].rule_type, "cxx_binary");
+        assert!(graph.nodes["root//lib:lib"].deps.is_empty());
+        assert!(!graph.nodes.contains_key("root//third_party:zlib"));
+    }
+
+    #[test]
+    fn keys_cquery_labels_without_configuration() {
+        let output = r#"{
+            "root//app:main (cfg//:linux#abc123)": {"buck.deps": ["root//lib:lib (cfg//:linux#abc123)"]},
+            "root//lib:lib (cfg//:linux#abc123)": {"buck.deps": [], "buck.oncall": "build_infra"}
+        }"#;
+        let graph = DepGraph::from_query_output(output).unwrap();
+        assert_eq!(
+            graph.nodes.keys().collect::<Vec<_>>(),
+            vec!["root//app:main", "root//lib:lib"]
+        );
+        assert_eq!(graph.nodes["root//app:main"].deps, vec!["root//lib:lib"]);
+        assert_eq!(graph.nodes["root//lib:lib"].rule_type, "unknown");
+        assert_eq!(
+            graph.nodes["root//lib:lib"].oncall.as_deref(),
+            Some("build_infra")
+        );
+    }
+
+    #[test]
+    fn rejects_unexpected_output() {
+        assert!(DepGraph::from_query_output("root//app:main").is_err());
+    }
+
+    fn quoted_graph() -> DepGraph {
+        let output = r#"{
+            "root//app:\"main\"": {"buck.type": "rules.bzl:genrule", "buck.deps": ["root//lib:a\\b"], "buck.oncall": "<team>"},
+            "root//lib:a\\b": {"buck.type": "rules.bzl:genrule", "buck.deps": []}
+        }"#;
+        DepGraph::from_query_output(output).unwrap()
+    }
+
+    #[test]
+    fn escapes_dot_strings() {
+        let dot = quoted_graph().to_dot();
+        assert!(dot.contains(
+            "    \"root//app:\\\"main\\\"\" [label=\"root//app:\\\"main\\\"\\ngenrule\\noncall: <team>\"];\n"
+        ));
+        assert!(dot.contains("    \"root//lib:a\\\\b\" [label=\"root//lib:a\\\\b\\ngenrule\"];\n"));
+        assert!(dot.contains("    \"root//app:\\\"main\\\"\" -> \"root//lib:a\\\\b\";\n"));
+    }
+
+    #[test]
+    fn escapes_mermaid_labels() {
+        let mermaid = quoted_graph().to_mermaid();
+        assert_eq!(
+            mermaid,
+            "graph LR\n    n0[\"root//app:#quot;main#quot;<br/>genrule<br/>oncall: #lt;team#gt;\"]\n    n1[\"root//lib:a\\b<br/>genrule\"]\n    n0 --> n1\n"
+        );
+    }
+
+    #[test]
+    fn zero_depth_is_unlimited() {
+        assert_eq!(deps_query("root//app:main", 0), "deps(root//app:main)");
+        assert_eq!(deps_query("root//app:main", 2), "deps(root//app:main, 2)");
+    }
+}
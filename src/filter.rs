@@ -0,0 +1,390 @@
+use crate::buck::BuckTarget;
+
+/// A field of `BuckTarget` that can be used as a search predicate, e.g. `rule:rust_library`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TargetField {
+    Name,
+    Rule,
+    Oncall,
+    Label,
+    Dep,
+    Visibility,
+    Platform,
+}
+
+impl TargetField {
+    fn from_prefix(prefix: &str) -> Option<Self> {
+        match prefix.to_lowercase().as_str() {
+            "name" => Some(TargetField::Name),
+            "rule" | "type" | "kind" => Some(TargetField::Rule),
+            "oncall" | "owner" => Some(TargetField::Oncall),
+            "label" | "labels" => Some(TargetField::Label),
+            "dep" | "deps" => Some(TargetField::Dep),
+            "vis" | "visibility" => Some(TargetField::Visibility),
+            "platform" => Some(TargetField::Platform),
+            _ => None,
+        }
+    }
+}
+
+/// Search expression over `BuckTarget` fields
+///
+/// Grammar (case-insensitive, values are substring matches):
+/// - `rule:rust_library oncall:foo` - whitespace separated terms are ANDed (`&`/`AND` also work)
+/// - `rule:rust_binary | rule:rust_test` - `|`/`OR` combines alternatives
+/// - `!dep://foo:bar` or `-dep://foo:bar` or `NOT dep://foo:bar` - negation
+/// - `(a | b) c` - parentheses group terms
+/// - any term without a known `field:` prefix matches the target display title
+#[derive(Debug, Clone, PartialEq)]
+pub enum TargetFilter {
+    All,
+    Text(String),
+    Field(TargetField, String),
+    Not(Box<TargetFilter>),
+    And(Vec<TargetFilter>),
+    Or(Vec<TargetFilter>),
+}
+
+impl TargetFilter {
+    /// Parse a search query. Parsing is lenient: unbalanced parentheses are ignored
+    /// and an empty query matches everything.
+    pub fn parse(query: &str) -> Self {
+        let tokens = tokenize(query);
+        let mut parser = Parser { tokens, pos: 0 };
+        let filter = parser.parse_or();
+        // Anything left over (e.g. a stray ")") is ANDed with what we parsed so far
+        let mut rest = vec![filter];
+        while parser.pos < parser.tokens.len() {
+            if parser.peek() == Some(")") {
+                parser.pos += 1;
+                continue;
+            }
+            rest.push(parser.parse_or());
+        }
+        if rest.len() == 1 {
+            rest.pop().unwrap()
+        } else {
+            TargetFilter::And(rest)
+        }
+    }
+
+    pub fn matches(&self, target: &BuckTarget) -> bool {
+        match self {
+            TargetFilter::All => true,
+            TargetFilter::Text(text) => contains_ignore_case(&target.display_title(), text),
+            TargetFilter::Field(field, value) => match field {
+                TargetField::Name => contains_ignore_case(&target.name, value),
+                TargetField::Rule => contains_ignore_case(&target.rule_type, value),
+                TargetField::Oncall => target
+                    .oncall
+                    .as_deref()
+                    .is_some_and(|oncall| contains_ignore_case(oncall, value)),
+                TargetField::Label => target.labels.iter().any(|l| contains_ignore_case(l, value)),
+                TargetField::Dep => target.deps.iter().any(|d| contains_ignore_case(d, value)),
+                TargetField::Visibility => target
+                    .visibility
+                    .iter()
+                    .any(|v| contains_ignore_case(v, value)),
+                TargetField::Platform => target
+                    .default_target_platform
+                    .as_deref()
+                    .is_some_and(|p| contains_ignore_case(p, value)),
+            },
+            TargetFilter::Not(inner) => !inner.matches(target),
+            TargetFilter::And(filters) => filters.iter().all(|f| f.matches(target)),
+            TargetFilter::Or(filters) => filters.iter().any(|f| f.matches(target)),
+        }
+    }
+
+    /// Whether the query uses anything beyond plain text (fields or operators).
+    /// Plain queries keep the substring highlighting in the UI.
+    pub fn is_structured(&self) -> bool {
+        !matches!(self, TargetFilter::All | TargetFilter::Text(_))
+    }
+}
+
+fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    haystack.to_lowercase().contains(&needle.to_lowercase())
+}
+
+fn tokenize(query: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in query.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if in_quotes => current.push(c),
+            '(' | ')' | '|' | '&' => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+                tokens.push(c.to_string());
+            }
+            c if c.is_whitespace() => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+struct Parser {
+    tokens: Vec<String>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(|s| s.as_str())
+    }
+
+    fn parse_or(&mut self) -> TargetFilter {
+        let mut alternatives = vec![self.parse_and()];
+        while matches!(self.peek(), Some("|") | Some("OR") | Some("or")) {
+            self.pos += 1;
+            alternatives.push(self.parse_and());
+        }
+        if alternatives.len() == 1 {
+            alternatives.pop().unwrap()
+        } else {
+            TargetFilter::Or(alternatives)
+        }
+    }
+
+    fn parse_and(&mut self) -> TargetFilter {
+        let mut terms = Vec::new();
+        loop {
+            match self.peek() {
+                None | Some(")") | Some("|") | Some("OR") | Some("or") => break,
+                Some("&") | Some("AND") | Some("and") => self.pos += 1,
+                _ => terms.push(self.parse_unary()),
+            }
+        }
+        match terms.len() {
+            0 => TargetFilter::All,
+            1 => terms.pop().unwrap(),
+            _ => TargetFilter::And(terms),
+        }
+    }
+
+    fn parse_unary(&mut self) -> TargetFilter {
+        let token = self.tokens[self.pos].clone();
+        self.pos += 1;
+
+        if token == "NOT" || token == "not" || token == "!" || token == "-" {
+            return match self.peek() {
+                None | Some(")") | Some("|") => TargetFilter::Text(token),
+                _ => TargetFilter::Not(Box::new(self.parse_unary())),
+            };
+        }
+
+        if token == "(" {
+            let inner = self.parse_or();
+            if self.peek() == Some(")") {
+                self.pos += 1;
+            }
+            return inner;
+        }
+
+        if let Some(rest) = token.strip_prefix('!').or_else(|| token.strip_prefix('-'))
+            && !rest.is_empty()
+        {
+            return TargetFilter::Not(Box::new(parse_term(rest)));
+        }
+
+        parse_term(&token)
+    }
+}
+
+fn parse_term(token: &str) -> TargetFilter {
+    if let Some((prefix, value)) = token.split_once(':')
+        && let Some(field) = TargetField::from_prefix(prefix)
+    {
+        return TargetFilter::Field(field, value.to_string());
+    }
+    TargetFilter::Text(token.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn target(name: &str, rule: &str, oncall: Option<&str>, deps: &[&str]) -> BuckTarget {
+        BuckTarget {
+            full_target_label_name: format!("root//foo:{}", name),
+            name: name.to_string(),
+            rule_type: rule.to_string(),
+            path: PathBuf::from("foo"),
+            deps: deps.iter().map(|d| d.to_string()).collect(),
+            details_loaded: false,
+            package: Some("root//foo".to_string()),
+            oncall: oncall.map(|o| o.to_string()),
+            visibility: vec!["PUBLIC".to_string()],
+            default_target_platform: None,
+            labels: vec!["ci_skip".to_string()],
+            attributes: serde_json::Map::new(),
+        }
+    }
+
+    fn text(s: &str) -> TargetFilter {
+        TargetFilter::Text(s.to_string())
+    }
+
+    fn field(field: TargetField, s: &str) -> TargetFilter {
+        TargetFilter::Field(field, s.to_string())
+    }
+
+    #[test]
+    fn empty_query_matches_everything() {
+        assert_eq!(TargetFilter::parse(""), TargetFilter::All);
+        assert_eq!(TargetFilter::parse("   "), TargetFilter::All);
+        assert!(!TargetFilter::parse("").is_structured());
+    }
+
+    #[test]
+    fn plain_text_is_not_structured() {
+        assert_eq!(TargetFilter::parse("server"), text("server"));
+        assert!(!TargetFilter::parse("server").is_structured());
+    }
+
+    #[test]
+    fn field_prefixes_and_aliases() {
+        assert_eq!(
+            TargetFilter::parse("rule:rust_library"),
+            field(TargetField::Rule, "rust_library")
+        );
+        assert_eq!(
+            TargetFilter::parse("KIND:rust_test"),
+            field(TargetField::Rule, "rust_test")
+        );
+        assert_eq!(
+            TargetFilter::parse("vis:PUBLIC"),
+            field(TargetField::Visibility, "PUBLIC")
+        );
+        // Only the first `:` separates the field, labels keep theirs
+        assert_eq!(
+            TargetFilter::parse("dep://foo:bar"),
+            field(TargetField::Dep, "//foo:bar")
+        );
+    }
+
+    #[test]
+    fn unknown_field_is_text() {
+        assert_eq!(TargetFilter::parse("color:red"), text("color:red"));
+        assert_eq!(TargetFilter::parse(":bar"), text(":bar"));
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert_eq!(
+            TargetFilter::parse("a b | c"),
+            TargetFilter::Or(vec![
+                TargetFilter::And(vec![text("a"), text("b")]),
+                text("c")
+            ])
+        );
+        assert_eq!(
+            TargetFilter::parse("a OR b AND c"),
+            TargetFilter::Or(vec![
+                text("a"),
+                TargetFilter::And(vec![text("b"), text("c")])
+            ])
+        );
+        assert_eq!(
+            TargetFilter::parse("a & b"),
+            TargetFilter::And(vec![text("a"), text("b")])
+        );
+    }
+
+    #[test]
+    fn negation_forms() {
+        let negated = TargetFilter::Not(Box::new(field(TargetField::Label, "x")));
+        assert_eq!(TargetFilter::parse("!label:x"), negated);
+        assert_eq!(TargetFilter::parse("-label:x"), negated);
+        assert_eq!(TargetFilter::parse("NOT label:x"), negated);
+        assert_eq!(TargetFilter::parse("not label:x"), negated);
+        // A trailing operator has nothing to negate and is searched for as text
+        assert_eq!(TargetFilter::parse("NOT"), text("NOT"));
+    }
+
+    #[test]
+    fn negation_binds_tighter_than_and() {
+        assert_eq!(
+            TargetFilter::parse("!a b"),
+            TargetFilter::And(vec![TargetFilter::Not(Box::new(text("a"))), text("b")])
+        );
+        assert_eq!(
+            TargetFilter::parse("NOT (a | b)"),
+            TargetFilter::Not(Box::new(TargetFilter::Or(vec![text("a"), text("b")])))
+        );
+    }
+
+    #[test]
+    fn parentheses_group() {
+        assert_eq!(
+            TargetFilter::parse("rule:x (oncall:a | oncall:b)"),
+            TargetFilter::And(vec![
+                field(TargetField::Rule, "x"),
+                TargetFilter::Or(vec![
+                    field(TargetField::Oncall, "a"),
+                    field(TargetField::Oncall, "b"),
+                ]),
+            ])
+        );
+    }
+
+    #[test]
+    fn unbalanced_parentheses_are_lenient() {
+        assert_eq!(
+            TargetFilter::parse("(a b"),
+            TargetFilter::And(vec![text("a"), text("b")])
+        );
+        assert_eq!(
+            TargetFilter::parse("a ) b"),
+            TargetFilter::And(vec![text("a"), text("b")])
+        );
+    }
+
+    #[test]
+    fn quotes_keep_spaces_and_operators() {
+        assert_eq!(TargetFilter::parse("\"a b\""), text("a b"));
+        assert_eq!(
+            TargetFilter::parse("name:\"x | y\""),
+            field(TargetField::Name, "x | y")
+        );
+    }
+
+    #[test]
+    fn matches_fields_case_insensitively() {
+        let lib = target("lib", "rust_library", Some("Team"), &["root//bar:baz"]);
+        assert!(TargetFilter::parse("rule:RUST_LIB").matches(&lib));
+        assert!(TargetFilter::parse("oncall:team").matches(&lib));
+        assert!(TargetFilter::parse("dep://bar:baz").matches(&lib));
+        assert!(TargetFilter::parse("label:ci").matches(&lib));
+        assert!(TargetFilter::parse("vis:public").matches(&lib));
+        assert!(!TargetFilter::parse("platform:linux").matches(&lib));
+        assert!(!TargetFilter::parse("rule:rust_binary").matches(&lib));
+    }
+
+    #[test]
+    fn matches_combinations() {
+        let lib = target("lib", "rust_library", Some("a"), &[]);
+        let test = target("lib_test", "rust_test", None, &["root//foo:lib"]);
+        let query = TargetFilter::parse("(rule:rust_library | rule:rust_test) !oncall:a");
+        assert!(!query.matches(&lib));
+        assert!(query.matches(&test));
+
+        let query = TargetFilter::parse("rule:rust NOT dep://foo:lib");
+        assert!(query.matches(&lib));
+        assert!(!query.matches(&test));
+    }
+}
@@ -0,0 +1,92 @@
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+
+use crate::buck::BuckProject;
+
+const MAX_HISTORY: usize = 50;
+
+/// Prompt jumping to a label pasted from a log or a review: `cell//path:name`,
+/// `//path:name`, `:name` in the selected package or a package directory
+pub struct GotoPrompt {
+    pub active: bool,
+    pub input: String,
+    // Why the input could not be turned into a label
+    pub error: Option<String>,
+    history: Vec<String>,
+    history_index: Option<usize>,
+}
+
+impl GotoPrompt {
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            input: String::new(),
+            error: None,
+            history: Vec::new(),
+            history_index: None,
+        }
+    }
+
+    pub fn open(&mut self) {
+        self.active = true;
+        self.input.clear();
+        self.error = None;
+        self.history_index = None;
+    }
+
+    pub fn close(&mut self) {
+        self.active = false;
+    }
+
+    /// Handle a key while the prompt is open. Returns the full label to jump to, if any.
+    pub fn handle_key(&mut self, key: KeyEvent, project: &BuckProject) -> Option<String> {
+        match key.code {
+            KeyCode::Esc => self.close(),
+            KeyCode::Enter => match project.absolute_label(&self.input) {
+                Ok(label) => {
+                    self.remember(self.input.trim().to_string());
+                    return Some(label);
+                }
+                Err(e) => self.error = Some(e.to_string()),
+            },
+            KeyCode::Up if !self.history.is_empty() => {
+                let index = match self.history_index {
+                    Some(i) => i.saturating_sub(1),
+                    None => self.history.len() - 1,
+                };
+                self.history_index = Some(index);
+                self.input = self.history[index].clone();
+            }
+            KeyCode::Down => {
+                if let Some(i) = self.history_index {
+                    if i + 1 < self.history.len() {
+                        self.history_index = Some(i + 1);
+                        self.input = self.history[i + 1].clone();
+                    } else {
+                        self.history_index = None;
+                        self.input.clear();
+                    }
+                }
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+                self.error = None;
+            }
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                self.error = None;
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn remember(&mut self, input: String) {
+        self.history.retain(|entry| *entry != input);
+        self.history.push(input);
+        if self.history.len() > MAX_HISTORY {
+            self.history.remove(0);
+        }
+        self.history_index = None;
+    }
+}
@@ -0,0 +1,390 @@
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::buck::BuckProject;
+use crate::buck::CommandOutput;
+use crate::buck::create_command_task;
+use crate::export::DepGraph;
+use crate::scheduler::Scheduler;
+use crate::scheduler::TaskId;
+
+const MAX_LEVELS: u32 = 6;
+const MAX_BOX_LABEL: usize = 28;
+// Vertical distance between two layers, boxes are 3 rows high
+pub const LAYER_SPACING: f64 = 6.0;
+const NODE_GAP: f64 = 3.0;
+const PAN_STEP_X: f64 = 8.0;
+const PAN_STEP_Y: f64 = 3.0;
+
+/// A node of the neighbourhood placed on the canvas.
+/// Layers above the focus (negative) are dependents, layers below are dependencies.
+#[derive(Debug, Clone)]
+pub struct PlacedNode {
+    pub label: String,
+    pub rule_type: String,
+    pub text: String,
+    pub layer: i32,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+}
+
+/// Graph canvas of a target's neighbourhood: `levels` of deps below it and rdeps above it
+pub struct GraphView {
+    pub active: bool,
+    pub focus: String,
+    pub levels: u32,
+    pub nodes: Vec<PlacedNode>,
+    // Indices into `nodes`, from the dependent to its dependency
+    pub edges: Vec<(usize, usize)>,
+    pub selected: usize,
+    pub pan: (f64, f64),
+    pub running: bool,
+    pub error: Option<String>,
+    // Previous focuses, Backspace goes back
+    history: Vec<String>,
+    current_dir: PathBuf,
+    output: CommandOutput,
+    task_id: Option<TaskId>,
+}
+
+impl GraphView {
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            focus: String::new(),
+            levels: 1,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            selected: 0,
+            pan: (0.0, 0.0),
+            running: false,
+            error: None,
+            history: Vec::new(),
+            current_dir: PathBuf::new(),
+            output: Arc::new(Mutex::new(None)),
+            task_id: None,
+        }
+    }
+
+    pub fn open(&mut self, focus: String, project: &BuckProject, scheduler: &Scheduler) {
+        self.active = true;
+        self.history.clear();
+        self.current_dir = project.root_path.clone();
+        self.focus = focus;
+        self.run(project, scheduler);
+    }
+
+    pub fn close(&mut self) {
+        self.active = false;
+    }
+
+    pub fn selected_node(&self) -> Option<&PlacedNode> {
+        self.nodes.get(self.selected)
+    }
+
+    /// Handle a key while the canvas is open.
+    /// Returns the label the user wants to jump to in the explorer, if any.
+    pub fn handle_key(
+        &mut self,
+        key: KeyEvent,
+        project: &BuckProject,
+        scheduler: &Scheduler,
+    ) -> Option<String> {
+        match key.code {
+            KeyCode::Esc => self.close(),
+            KeyCode::Char('h') => self.select_in_layer(-1),
+            KeyCode::Char('l') => self.select_in_layer(1),
+            KeyCode::Char('k') => self.select_in_next_layer(-1),
+            KeyCode::Char('j') => self.select_in_next_layer(1),
+            KeyCode::Left => self.pan.0 -= PAN_STEP_X,
+            KeyCode::Right => self.pan.0 += PAN_STEP_X,
+            KeyCode::Up => self.pan.1 += PAN_STEP_Y,
+            KeyCode::Down => self.pan.1 -= PAN_STEP_Y,
+            KeyCode::Char('c') => self.center_on_selected(),
+            KeyCode::Char('+') | KeyCode::Char('=') if self.levels < MAX_LEVELS => {
+                self.levels += 1;
+                self.run(project, scheduler);
+            }
+            KeyCode::Char('-') if self.levels > 1 => {
+                self.levels -= 1;
+                self.run(project, scheduler);
+            }
+            KeyCode::Enter => {
+                if let Some(node) = self.selected_node()
+                    && node.label != self.focus
+                {
+                    let label = node.label.clone();
+                    self.history.push(std::mem::replace(&mut self.focus, label));
+                    self.run(project, scheduler);
+                }
+            }
+            KeyCode::Backspace => {
+                if let Some(previous) = self.history.pop() {
+                    self.focus = previous;
+                    self.run(project, scheduler);
+                }
+            }
+            KeyCode::Char('o') => return self.selected_node().map(|n| n.label.clone()),
+            _ => {}
+        }
+        None
+    }
+
+    fn select_in_layer(&mut self, step: i32) {
+        let Some(current) = self.selected_node() else {
+            return;
+        };
+        let layer = current.layer;
+        let x = current.x;
+        let candidate = self
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| n.layer == layer && (n.x - x) * step as f64 > 0.0)
+            .min_by(|(_, a), (_, b)| (a.x - x).abs().total_cmp(&(b.x - x).abs()))
+            .map(|(i, _)| i);
+        if let Some(index) = candidate {
+            self.selected = index;
+            self.follow_selected();
+        }
+    }
+
+    fn select_in_next_layer(&mut self, step: i32) {
+        let Some(current) = self.selected_node() else {
+            return;
+        };
+        let layer = current.layer + step;
+        let x = current.x;
+        let candidate = self
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| n.layer == layer)
+            .min_by(|(_, a), (_, b)| (a.x - x).abs().total_cmp(&(b.x - x).abs()))
+            .map(|(i, _)| i);
+        if let Some(index) = candidate {
+            self.selected = index;
+            self.follow_selected();
+        }
+    }
+
+    fn center_on_selected(&mut self) {
+        if let Some(node) = self.selected_node() {
+            self.pan = (node.x, node.y);
+        }
+    }
+
+    /// Keep the selection roughly in view without recentering on every move
+    fn follow_selected(&mut self) {
+        if let Some(node) = self.selected_node()
+            && ((node.x - self.pan.0).abs() > 30.0
+                || (node.y - self.pan.1).abs() > 2.0 * LAYER_SPACING)
+        {
+            self.center_on_selected();
+        }
+    }
+
+    fn run(&mut self, project: &BuckProject, scheduler: &Scheduler) {
+        if let Some(task_id) = self.task_id.take() {
+            scheduler.cancel(task_id);
+        }
+
+        // Dependents are looked up in the cell of the focus
+        let cell = self.focus.split("//").next().unwrap_or_default();
+        let query = format!(
+            "deps({focus}, {levels}) + rdeps({cell}//..., {focus}, {levels})",
+            focus = self.focus,
+            levels = self.levels,
+            cell = cell,
+        );
+        let mut cmds = project.query_command(query);
+        cmds.push("--output-all-attributes".to_owned());
+        let task = create_command_task(cmds, self.current_dir.clone(), self.output.clone());
+        self.task_id = Some(task.id);
+        scheduler.dispatch_micro(task);
+
+        self.running = true;
+        self.error = None;
+    }
+
+    /// Pick up the query output, called from the UI loop
+    pub async fn update_results(&mut self) {
+        let Some(result) = self.output.lock().await.take() else {
+            return;
+        };
+        self.running = false;
+        self.task_id = None;
+
+        match result.and_then(|output| DepGraph::from_query_output(&output)) {
+            Ok(graph) => self.layout(&graph),
+            Err(e) => {
+                self.nodes.clear();
+                self.edges.clear();
+                self.error = Some(e.to_string());
+            }
+        }
+    }
+
+    /// Place the nodes in layers by their distance to the focus, ordering each layer by
+    /// the position of its neighbours in the layer closer to the focus to limit crossings
+    fn layout(&mut self, graph: &DepGraph) {
+        self.nodes.clear();
+        self.edges.clear();
+        self.selected = 0;
+
+        let Some(focus) = graph
+            .nodes
+            .keys()
+            .find(|label| **label == self.focus || label.ends_with(self.focus.as_str()))
+            .cloned()
+        else {
+            self.error = Some(format!("{} is not in the query result", self.focus));
+            return;
+        };
+        self.focus = focus.clone();
+
+        let mut rdeps: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (label, node) in &graph.nodes {
+            for dep in &node.deps {
+                rdeps.entry(dep.as_str()).or_default().push(label.as_str());
+            }
+        }
+
+        // Deps take precedence when a node is both above and below the focus (cycles)
+        let mut layers: HashMap<&str, i32> = HashMap::from([(focus.as_str(), 0)]);
+        let mut queue = VecDeque::from([focus.as_str()]);
+        while let Some(label) = queue.pop_front() {
+            let layer = layers[label];
+            for dep in graph
+                .nodes
+                .get(label)
+                .map(|n| n.deps.as_slice())
+                .unwrap_or_default()
+            {
+                if !layers.contains_key(dep.as_str()) {
+                    layers.insert(dep, layer + 1);
+                    queue.push_back(dep);
+                }
+            }
+        }
+        let mut queue = VecDeque::from([focus.as_str()]);
+        while let Some(label) = queue.pop_front() {
+            let layer = layers[label].min(0);
+            for dependent in rdeps.get(label).into_iter().flatten() {
+                if !layers.contains_key(dependent) {
+                    layers.insert(dependent, layer - 1);
+                    queue.push_back(dependent);
+                }
+            }
+        }
+
+        let mut by_layer: BTreeMap<i32, Vec<&str>> = BTreeMap::new();
+        for (label, layer) in &layers {
+            by_layer.entry(*layer).or_default().push(label);
+        }
+
+        // Place the focus layer first, then outwards in both directions
+        let mut order: Vec<i32> = by_layer.keys().filter(|l| **l >= 0).copied().collect();
+        order.extend(by_layer.keys().filter(|l| **l < 0).rev().copied());
+
+        let mut positions: HashMap<String, f64> = HashMap::new();
+        for layer in order {
+            let mut labels = by_layer[&layer].clone();
+            labels.sort();
+            let inner = layer - layer.signum();
+            let barycenter = |label: &str| -> Option<f64> {
+                let neighbours: Vec<f64> = if layer > 0 {
+                    rdeps
+                        .get(label)
+                        .into_iter()
+                        .flatten()
+                        .copied()
+                        .collect::<Vec<_>>()
+                } else {
+                    graph.nodes[label].deps.iter().map(|d| d.as_str()).collect()
+                }
+                .into_iter()
+                .filter(|n| layers.get(n) == Some(&inner))
+                .filter_map(|n| positions.get(n).copied())
+                .collect();
+                (!neighbours.is_empty())
+                    .then(|| neighbours.iter().sum::<f64>() / neighbours.len() as f64)
+            };
+            let mut keyed: Vec<(f64, &str)> = labels
+                .iter()
+                .enumerate()
+                .map(|(i, label)| (barycenter(label).unwrap_or(i as f64), *label))
+                .collect();
+            keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let placed: Vec<PlacedNode> = keyed
+                .iter()
+                .map(|(_, label)| {
+                    let text = box_text(label);
+                    PlacedNode {
+                        label: label.to_string(),
+                        rule_type: graph.nodes[*label].rule_type.clone(),
+                        width: text.chars().count() as f64 + 2.0,
+                        text,
+                        layer,
+                        x: 0.0,
+                        y: -(layer as f64) * LAYER_SPACING,
+                    }
+                })
+                .collect();
+
+            // Center the layer under the focus
+            let total: f64 = placed.iter().map(|n| n.width).sum::<f64>()
+                + NODE_GAP * placed.len().saturating_sub(1) as f64;
+            let mut x = -total / 2.0;
+            for mut node in placed {
+                node.x = x + node.width / 2.0;
+                x += node.width + NODE_GAP;
+                positions.insert(node.label.clone(), node.x);
+                self.nodes.push(node);
+            }
+        }
+
+        let indices: HashMap<&str, usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n.label.as_str(), i))
+            .collect();
+        for (label, node) in &graph.nodes {
+            let Some(&from) = indices.get(label.as_str()) else {
+                continue;
+            };
+            for dep in &node.deps {
+                if let Some(&to) = indices.get(dep.as_str()) {
+                    self.edges.push((from, to));
+                }
+            }
+        }
+
+        self.selected = indices.get(focus.as_str()).copied().unwrap_or(0);
+        self.pan = (0.0, 0.0);
+    }
+}
+
+/// Label shown in a box: the cell is dropped and long labels keep their end
+fn box_text(label: &str) -> String {
+    let text = label
+        .split_once("//")
+        .map(|(_, rest)| rest)
+        .unwrap_or(label);
+    let count = text.chars().count();
+    if count <= MAX_BOX_LABEL {
+        text.to_string()
+    } else {
+        let tail: String = text.chars().skip(count - (MAX_BOX_LABEL - 1)).collect();
+        format!("…{}", tail)
+    }
+}
@@ -0,0 +1,170 @@
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::PathBuf;
+
+const MAX_LOCATIONS: usize = 100;
+
+/// A place in the explorer: the listed directory, the one selected in it and the target
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Location {
+    pub current_path: PathBuf,
+    pub selected_directory: PathBuf,
+    // Full label of the selected target
+    pub target: Option<String>,
+}
+
+/// Vim-style jump list. `position` is the entry being shown while moving back and forth,
+/// `entries.len()` when at the live location.
+#[derive(Debug, Default)]
+pub struct JumpList {
+    pub entries: Vec<Location>,
+    pub position: usize,
+}
+
+impl JumpList {
+    /// Remember `from` before jumping away from it. Forward entries are dropped, as in vim.
+    pub fn record(&mut self, from: Location) {
+        self.entries.truncate(self.position);
+        if self.entries.last() != Some(&from) {
+            self.entries.push(from);
+        }
+        if self.entries.len() > MAX_LOCATIONS {
+            self.entries.remove(0);
+        }
+        self.position = self.entries.len();
+    }
+
+    /// Step back from `current`, which is kept to come forward again
+    pub fn back(&mut self, current: Location) -> Option<Location> {
+        if self.position == 0 {
+            return None;
+        }
+        if self.position == self.entries.len() {
+            if self.entries.last() == Some(&current) {
+                // Already recorded, e.g. right after a jump back to it. Nothing is before it
+                // when it is the only entry, keep the position at the live location then.
+                if self.position == 1 {
+                    return None;
+                }
+                self.position -= 1;
+            } else {
+                self.entries.push(current);
+            }
+        }
+        self.position -= 1;
+        self.entries.get(self.position).cloned()
+    }
+
+    pub fn forward(&mut self) -> Option<Location> {
+        if self.position + 1 >= self.entries.len() {
+            return None;
+        }
+        self.position += 1;
+        self.entries.get(self.position).cloned()
+    }
+
+    /// Move to the entry at `index`, keeping the others
+    pub fn go_to(&mut self, index: usize) -> Option<Location> {
+        let location = self.entries.get(index).cloned()?;
+        self.position = index;
+        Some(location)
+    }
+}
+
+/// Popup listing the jump list, most recent first
+pub struct JumpListView {
+    pub active: bool,
+    // Index into the jump list entries
+    pub selected: usize,
+}
+
+impl JumpListView {
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            selected: 0,
+        }
+    }
+
+    pub fn open(&mut self, jump_list: &JumpList) {
+        self.active = true;
+        self.selected = jump_list
+            .position
+            .min(jump_list.entries.len().saturating_sub(1));
+    }
+
+    pub fn close(&mut self) {
+        self.active = false;
+    }
+
+    /// Handle a key while the popup is open. Returns the index of the entry to go to, if any.
+    pub fn handle_key(&mut self, key: KeyEvent, jump_list: &JumpList) -> Option<usize> {
+        let count = jump_list.entries.len();
+        match key.code {
+            KeyCode::Esc => self.close(),
+            // The list is drawn newest first
+            KeyCode::Char('j') | KeyCode::Down if count > 0 => {
+                self.selected = (self.selected + count - 1) % count;
+            }
+            KeyCode::Char('k') | KeyCode::Up if count > 0 => {
+                self.selected = (self.selected + 1) % count;
+            }
+            KeyCode::Enter if self.selected < count => {
+                self.close();
+                return Some(self.selected);
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(name: &str) -> Location {
+        Location {
+            current_path: PathBuf::from(name),
+            selected_directory: PathBuf::from(name),
+            target: None,
+        }
+    }
+
+    #[test]
+    fn back_and_forward() {
+        let mut jumps = JumpList::default();
+        jumps.record(location("a"));
+        jumps.record(location("b"));
+        assert_eq!(jumps.back(location("c")), Some(location("b")));
+        assert_eq!(jumps.back(location("b")), Some(location("a")));
+        assert_eq!(jumps.back(location("a")), None);
+        assert_eq!(jumps.forward(), Some(location("b")));
+        assert_eq!(jumps.forward(), Some(location("c")));
+        assert_eq!(jumps.forward(), None);
+    }
+
+    #[test]
+    fn record_drops_forward_entries() {
+        let mut jumps = JumpList::default();
+        jumps.record(location("a"));
+        jumps.record(location("b"));
+        jumps.back(location("c"));
+        jumps.back(location("b"));
+        jumps.record(location("a"));
+        assert_eq!(jumps.entries, vec![location("a")]);
+        assert_eq!(jumps.position, 1);
+    }
+
+    #[test]
+    fn back_from_the_only_entry_keeps_it() {
+        let mut jumps = JumpList::default();
+        jumps.record(location("a"));
+        assert_eq!(jumps.back(location("a")), None);
+        assert_eq!(jumps.position, 1);
+        jumps.record(location("b"));
+        assert_eq!(jumps.entries, vec![location("a"), location("b")]);
+    }
+}
@@ -1,14 +1,36 @@
 use anyhow::Context;
 use anyhow::Result;
 use clap::Parser;
+use std::path::PathBuf;
 use tracing_subscriber::fmt;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 
 mod app;
+mod attributes;
+mod bookmarks;
 mod buck;
+mod build_file;
+mod cells;
+mod changes;
+mod dep_path;
+mod dep_tree;
+mod editor;
 mod events;
+mod export;
+mod filter;
+mod goto;
+mod graph_view;
+mod jump_list;
+mod owner;
+mod platform;
+mod query_console;
+mod rdeps;
+mod recent;
 mod scheduler;
+mod session;
+mod sources;
+mod tabs;
 mod ui;
 use app::App;
 use tracing::info;
@@ -19,17 +41,65 @@ use tracing::info;
 struct Args {
     #[arg(short, long, help = "Path to the Buck2 project")]
     path: Option<String>,
+
+    #[arg(
+        long,
+        help = "Start at --path instead of restoring the previous session"
+    )]
+    fresh: bool,
+
+    #[arg(
+        long,
+        value_name = "PATTERN",
+        help = "Export the dependency graph of a target or package (e.g. //foo:bar or //foo:) and exit"
+    )]
+    export: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value = "dot",
+        help = "Format of the exported graph"
+    )]
+    format: export::ExportFormat,
+
+    #[arg(
+        long,
+        default_value_t = 0,
+        help = "Dependency depth to export, 0 for unlimited"
+    )]
+    depth: u32,
+
+    #[arg(
+        short,
+        long,
+        help = "File to write the exported graph to, stdout by default"
+    )]
+    output: Option<PathBuf>,
 }
 
-fn setup_logging() -> Result<tracing_appender::non_blocking::WorkerGuard> {
-    // Get standard log directory following XDG Base Directory specification
-    // Linux: ~/.local/state/buck-tui/
-    // macOS: ~/Library/Application Support/buck-tui/
-    // Windows: C:\Users\<user>\AppData\Local\buck-tui\
-    let log_dir = dirs::state_dir()
+/// Directory for logs and persisted state, following XDG Base Directory specification
+/// Linux: ~/.local/state/buck-tui/
+/// macOS: ~/Library/Application Support/buck-tui/
+/// Windows: C:\Users\<user>\AppData\Local\buck-tui\
+pub fn state_dir() -> Option<PathBuf> {
+    dirs::state_dir()
         .or_else(|| dirs::data_local_dir())
-        .context("Failed to determine log directory")?
-        .join("buck-tui");
+        .map(|dir| dir.join("buck-tui"))
+}
+
+/// File of per-project state under `state_dir`, e.g. `projects/home%me%repo/bookmarks.json`.
+/// The directory name is the project root with `/` escaped so it stays readable.
+pub fn project_state_file(project_root: &std::path::Path, name: &str) -> Option<PathBuf> {
+    let key = project_root
+        .to_string_lossy()
+        .trim_start_matches(['/', '\\'])
+        .replace(['/', '\\', ':'], "%");
+    state_dir().map(|dir| dir.join("projects").join(key).join(name))
+}
+
+fn setup_logging() -> Result<tracing_appender::non_blocking::WorkerGuard> {
+    let log_dir = state_dir().context("Failed to determine log directory")?;
 
     // Create log directory if it doesn't exist
     std::fs::create_dir_all(&log_dir)
@@ -58,7 +128,21 @@ async fn main() -> Result<()> {
     let args = Args::parse();
     let project_path = args.path.unwrap_or_else(|| ".".to_string());
 
+    // Headless mode, nothing to draw
+    if let Some(pattern) = &args.export {
+        return export::run_headless(
+            std::path::Path::new(&project_path),
+            pattern,
+            args.format,
+            args.depth,
+            args.output.as_deref(),
+        );
+    }
+
     let mut app = App::new(project_path).await?;
+    if !args.fresh {
+        app.restore_session();
+    }
 
     // Request targets for the initial current directory if it has Buck files
     app.initialize().await;
@@ -0,0 +1,184 @@
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use std::path::PathBuf;
+
+use crate::buck::BuckProject;
+use crate::build_file::BuildFileNames;
+use crate::query_console::ConsoleFocus;
+use crate::query_console::QueryResults;
+use crate::scheduler::Scheduler;
+
+const MAX_SUGGESTIONS: usize = 8;
+
+/// "Which target owns this file?" view, backed by `buck2 uquery "owner(<file>)"`.
+/// Paths are typed relative to the current directory, with completion from the file system.
+pub struct OwnerLookup {
+    pub active: bool,
+    pub focus: ConsoleFocus,
+    pub input: String,
+    pub suggestions: Vec<String>,
+    pub suggestion_selected: usize,
+    // File of the last lookup, relative to `base_dir`
+    pub file: String,
+    pub results: QueryResults,
+    base_dir: PathBuf,
+    build_files: BuildFileNames,
+}
+
+impl OwnerLookup {
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            focus: ConsoleFocus::Input,
+            input: String::new(),
+            suggestions: Vec::new(),
+            suggestion_selected: 0,
+            file: String::new(),
+            results: QueryResults::new(),
+            base_dir: PathBuf::new(),
+            build_files: BuildFileNames::new(),
+        }
+    }
+
+    /// Open the lookup, offering the files of the directory selected in the explorer
+    pub fn open(&mut self, project: &BuckProject) {
+        self.active = true;
+        self.focus = ConsoleFocus::Input;
+        self.base_dir = project.current_path.clone();
+        self.build_files = project.build_files.clone();
+        self.input = project
+            .selected_directory
+            .strip_prefix(&project.current_path)
+            .ok()
+            .filter(|relative| !relative.as_os_str().is_empty())
+            .map(|relative| format!("{}/", relative.display()))
+            .unwrap_or_default();
+        self.update_suggestions();
+    }
+
+    pub fn close(&mut self) {
+        self.active = false;
+    }
+
+    /// Handle a key while the view is open.
+    /// Returns the label of the owning target the user picked, if any.
+    pub fn handle_key(
+        &mut self,
+        key: KeyEvent,
+        _project: &BuckProject,
+        scheduler: &Scheduler,
+    ) -> Option<String> {
+        if key.code == KeyCode::Esc {
+            self.close();
+            return None;
+        }
+
+        match self.focus {
+            ConsoleFocus::Input => match key.code {
+                KeyCode::Enter => self.run(scheduler),
+                KeyCode::Down if !self.suggestions.is_empty() => {
+                    self.suggestion_selected =
+                        (self.suggestion_selected + 1) % self.suggestions.len();
+                }
+                KeyCode::Up if !self.suggestions.is_empty() => {
+                    self.suggestion_selected = (self.suggestion_selected + self.suggestions.len()
+                        - 1)
+                        % self.suggestions.len();
+                }
+                KeyCode::Tab => {
+                    if let Some(suggestion) = self.suggestions.get(self.suggestion_selected) {
+                        self.input = suggestion.clone();
+                        self.update_suggestions();
+                    } else if !self.results.labels.is_empty() {
+                        self.focus = ConsoleFocus::Results;
+                    }
+                }
+                KeyCode::Backspace => {
+                    self.input.pop();
+                    self.update_suggestions();
+                }
+                KeyCode::Char(c) => {
+                    self.input.push(c);
+                    self.update_suggestions();
+                }
+                _ => {}
+            },
+            ConsoleFocus::Results => match key.code {
+                KeyCode::Char('j') | KeyCode::Down => self.results.next(),
+                KeyCode::Char('k') | KeyCode::Up => self.results.prev(),
+                KeyCode::Tab | KeyCode::Char('i') => self.focus = ConsoleFocus::Input,
+                KeyCode::Enter => return self.results.selected_label().map(|s| s.to_string()),
+                _ => {}
+            },
+        }
+        None
+    }
+
+    /// Complete the last path component from the entries of the typed directory.
+    /// BUCK files are left out, they are owned by nothing.
+    fn update_suggestions(&mut self) {
+        self.suggestion_selected = 0;
+        let (dir_part, name_part) = match self.input.rsplit_once('/') {
+            Some((dir, name)) => (format!("{}/", dir), name),
+            None => (String::new(), self.input.as_str()),
+        };
+
+        let dir = self.base_dir.join(&dir_part);
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            self.suggestions.clear();
+            return;
+        };
+
+        let mut suggestions: Vec<String> = entries
+            .filter_map(|e| e.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if name.starts_with('.')
+                    || !name.starts_with(name_part)
+                    || self.build_files.is_build_file(&dir, &name)
+                {
+                    return None;
+                }
+                let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+                Some(if is_dir {
+                    format!("{}{}/", dir_part, name)
+                } else {
+                    format!("{}{}", dir_part, name)
+                })
+            })
+            .filter(|suggestion| *suggestion != self.input)
+            .collect();
+        suggestions.sort();
+        suggestions.truncate(MAX_SUGGESTIONS);
+        self.suggestions = suggestions;
+    }
+
+    fn run(&mut self, scheduler: &Scheduler) {
+        let file = self.input.trim().trim_end_matches('/').to_string();
+        if file.is_empty() {
+            return;
+        }
+
+        // owner() resolves paths against the working directory. The path is passed as an
+        // argument so quotes in it don't end the query string.
+        self.results.run(
+            vec![
+                "buck2".to_owned(),
+                "uquery".to_owned(),
+                "owner(%s)".to_owned(),
+                file.clone(),
+            ],
+            self.base_dir.clone(),
+            scheduler,
+        );
+        self.file = file;
+        self.suggestions.clear();
+    }
+
+    /// Pick up the query output, called from the UI loop
+    pub async fn update_results(&mut self) {
+        if self.results.update().await && !self.results.labels.is_empty() {
+            self.focus = ConsoleFocus::Results;
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+
+use crate::buck::BuckProject;
+use crate::query_console::QueryResults;
+use crate::scheduler::Scheduler;
+
+/// An entry of the platform picker
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlatformChoice {
+    // Back to the unconfigured graph (`buck2 uquery`)
+    Unconfigured,
+    Platform(String),
+}
+
+/// Session-wide target platform picker. Platforms are discovered with
+/// `buck2 uquery "kind('^platform$', <cell>//...)"` the first time it opens.
+pub struct PlatformPicker {
+    pub active: bool,
+    pub input: String,
+    pub choices: Vec<PlatformChoice>,
+    pub selected: usize,
+    // default_target_platform of the selected target
+    pub default_platform: Option<String>,
+    current_platform: Option<String>,
+    pub discovered: QueryResults,
+    discovery_started: bool,
+}
+
+impl PlatformPicker {
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            input: String::new(),
+            choices: Vec::new(),
+            selected: 0,
+            default_platform: None,
+            current_platform: None,
+            discovered: QueryResults::new(),
+            discovery_started: false,
+        }
+    }
+
+    /// Open the picker with the current platform preselected, or the selected target's
+    /// default platform when the session is still unconfigured
+    pub fn open(&mut self, project: &BuckProject, scheduler: &Scheduler) {
+        self.active = true;
+        self.input.clear();
+        self.default_platform = project
+            .get_selected_target()
+            .and_then(|t| t.default_target_platform.clone());
+        self.current_platform = project.target_platform.clone();
+
+        if !self.discovery_started {
+            self.discovery_started = true;
+            let cell = project
+                .cell_for_path(&project.current_path)
+                .unwrap_or_default();
+            self.discovered.run(
+                vec![
+                    "buck2".to_owned(),
+                    "uquery".to_owned(),
+                    format!("kind('^platform$', {}//...)", cell),
+                ],
+                project.root_path.clone(),
+                scheduler,
+            );
+        }
+
+        self.update_choices();
+        let preselected = match (&project.target_platform, &self.default_platform) {
+            (Some(platform), _) | (None, Some(platform)) => {
+                PlatformChoice::Platform(platform.clone())
+            }
+            (None, None) => PlatformChoice::Unconfigured,
+        };
+        self.selected = self
+            .choices
+            .iter()
+            .position(|c| *c == preselected)
+            .unwrap_or(0);
+    }
+
+    pub fn close(&mut self) {
+        self.active = false;
+    }
+
+    /// Handle a key while the picker is open. Returns the platform the user picked, if any.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<PlatformChoice> {
+        match key.code {
+            KeyCode::Esc => self.close(),
+            KeyCode::Down if !self.choices.is_empty() => {
+                self.selected = (self.selected + 1) % self.choices.len();
+            }
+            KeyCode::Up if !self.choices.is_empty() => {
+                self.selected = (self.selected + self.choices.len() - 1) % self.choices.len();
+            }
+            KeyCode::Enter => {
+                // A typed label that matches nothing is taken as is
+                let choice = match self.choices.get(self.selected) {
+                    Some(choice) => choice.clone(),
+                    None if self.input.contains("//") => {
+                        PlatformChoice::Platform(self.input.trim().to_string())
+                    }
+                    None => return None,
+                };
+                self.close();
+                return Some(choice);
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+                self.update_choices();
+            }
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                self.update_choices();
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Pick up the discovered platforms, called from the UI loop
+    pub async fn update_results(&mut self) {
+        if self.discovered.update().await {
+            self.update_choices();
+        }
+    }
+
+    fn update_choices(&mut self) {
+        let input = self.input.trim().to_lowercase();
+
+        let mut platforms: Vec<String> = Vec::new();
+        let known = self.current_platform.iter().chain(&self.default_platform);
+        for label in known.chain(&self.discovered.labels) {
+            if !platforms.contains(label) {
+                platforms.push(label.clone());
+            }
+        }
+
+        let mut choices = Vec::new();
+        if input.is_empty() {
+            choices.push(PlatformChoice::Unconfigured);
+        }
+        choices.extend(
+            platforms
+                .into_iter()
+                .filter(|p| p.to_lowercase().contains(&input))
+                .map(PlatformChoice::Platform),
+        );
+
+        self.choices = choices;
+        self.selected = self.selected.min(self.choices.len().saturating_sub(1));
+    }
+}
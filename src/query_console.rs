@@ -0,0 +1,326 @@
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyModifiers;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::debug;
+use tracing::warn;
+
+use crate::buck::BuckProject;
+use crate::buck::CommandOutput;
+use crate::buck::create_command_task;
+use crate::buck::parse_query_labels;
+use crate::scheduler::Scheduler;
+use crate::scheduler::TaskId;
+
+const HISTORY_FILE: &str = "query_history";
+const MAX_HISTORY: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QueryKind {
+    Uquery,
+    Cquery,
+}
+
+impl QueryKind {
+    pub fn command(&self) -> &'static str {
+        match self {
+            QueryKind::Uquery => "uquery",
+            QueryKind::Cquery => "cquery",
+        }
+    }
+
+    fn from_command(command: &str) -> Option<Self> {
+        match command {
+            "uquery" => Some(QueryKind::Uquery),
+            "cquery" => Some(QueryKind::Cquery),
+            _ => None,
+        }
+    }
+
+    fn toggle(&self) -> Self {
+        match self {
+            QueryKind::Uquery => QueryKind::Cquery,
+            QueryKind::Cquery => QueryKind::Uquery,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConsoleFocus {
+    Input,
+    Results,
+}
+
+/// Labels returned by a scheduled buck2 query, with the selection and the run state.
+/// Shared by the views that list query results.
+pub struct QueryResults {
+    pub labels: Vec<String>,
+    pub selected: usize,
+    pub running: bool,
+    pub error: Option<String>,
+    output: CommandOutput,
+    task_id: Option<TaskId>,
+}
+
+impl QueryResults {
+    pub fn new() -> Self {
+        Self {
+            labels: Vec::new(),
+            selected: 0,
+            running: false,
+            error: None,
+            output: Arc::new(Mutex::new(None)),
+            task_id: None,
+        }
+    }
+
+    /// Run `cmds` through the scheduler, cancelling the previous run if still going
+    pub fn run(&mut self, cmds: Vec<String>, current_dir: PathBuf, scheduler: &Scheduler) {
+        if let Some(task_id) = self.task_id.take() {
+            scheduler.cancel(task_id);
+        }
+
+        debug!("Running {}", cmds.join(" "));
+
+        let task = create_command_task(cmds, current_dir, self.output.clone());
+        self.task_id = Some(task.id);
+        scheduler.dispatch_micro(task);
+
+        self.running = true;
+        self.error = None;
+    }
+
+    /// Pick up the output of a finished run, called from the UI loop.
+    /// Returns true when new results arrived.
+    pub async fn update(&mut self) -> bool {
+        let Some(result) = self.output.lock().await.take() else {
+            return false;
+        };
+
+        self.running = false;
+        self.task_id = None;
+        self.selected = 0;
+        match result {
+            Ok(output) => {
+                self.labels = parse_query_labels(&output);
+                self.error = None;
+            }
+            Err(e) => {
+                self.labels.clear();
+                self.error = Some(e.to_string());
+            }
+        }
+        true
+    }
+
+    pub fn next(&mut self) {
+        if !self.labels.is_empty() {
+            self.selected = (self.selected + 1) % self.labels.len();
+        }
+    }
+
+    pub fn prev(&mut self) {
+        if !self.labels.is_empty() {
+            self.selected = (self.selected + self.labels.len() - 1) % self.labels.len();
+        }
+    }
+
+    pub fn selected_label(&self) -> Option<&str> {
+        self.labels.get(self.selected).map(|s| s.as_str())
+    }
+}
+
+/// REPL-style pane for running arbitrary `buck2 uquery`/`buck2 cquery` expressions
+pub struct QueryConsole {
+    pub active: bool,
+    pub focus: ConsoleFocus,
+    pub kind: QueryKind,
+    pub input: String,
+    pub results: QueryResults,
+    pub history: Vec<(QueryKind, String)>,
+    // Position in history while browsing with Up/Down, None when editing a new query
+    history_cursor: Option<usize>,
+}
+
+impl QueryConsole {
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            focus: ConsoleFocus::Input,
+            kind: QueryKind::Uquery,
+            input: String::new(),
+            results: QueryResults::new(),
+            history: Self::load_history(),
+            history_cursor: None,
+        }
+    }
+
+    pub fn open(&mut self) {
+        self.active = true;
+        self.focus = ConsoleFocus::Input;
+        self.history_cursor = None;
+    }
+
+    pub fn close(&mut self) {
+        self.active = false;
+    }
+
+    /// Handle a key while the console is open.
+    /// Returns the label the user picked from the results, if any.
+    pub fn handle_key(
+        &mut self,
+        key: KeyEvent,
+        project: &BuckProject,
+        scheduler: &Scheduler,
+    ) -> Option<String> {
+        if key.code == KeyCode::Esc {
+            self.close();
+            return None;
+        }
+
+        match self.focus {
+            ConsoleFocus::Input => match key.code {
+                KeyCode::Enter => self.run(project, scheduler),
+                KeyCode::Tab if !self.results.labels.is_empty() => {
+                    self.focus = ConsoleFocus::Results;
+                }
+                KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.kind = self.kind.toggle();
+                }
+                KeyCode::Up => self.history_prev(),
+                KeyCode::Down => self.history_next(),
+                KeyCode::Backspace => {
+                    self.input.pop();
+                }
+                KeyCode::Char(c) => self.input.push(c),
+                _ => {}
+            },
+            ConsoleFocus::Results => match key.code {
+                KeyCode::Char('j') | KeyCode::Down => self.results.next(),
+                KeyCode::Char('k') | KeyCode::Up => self.results.prev(),
+                KeyCode::Tab | KeyCode::Char('i') => self.focus = ConsoleFocus::Input,
+                KeyCode::Enter => return self.results.selected_label().map(|s| s.to_string()),
+                _ => {}
+            },
+        }
+        None
+    }
+
+    fn run(&mut self, project: &BuckProject, scheduler: &Scheduler) {
+        let query = self.input.trim().to_string();
+        if query.is_empty() {
+            return;
+        }
+
+        self.add_to_history(self.kind, query.clone());
+        self.history_cursor = None;
+
+        // `%Ss` (or `%s`) stands for the selected targets, passed as arguments in one run
+        let uses_targets = query.contains("%Ss") || query.contains("%s");
+        let labels = project.action_labels();
+        if uses_targets && labels.is_empty() {
+            self.results.error = Some("No targets selected for %Ss".to_string());
+            return;
+        }
+
+        let mut cmds = vec!["buck2".to_owned(), self.kind.command().to_owned(), query];
+        if uses_targets {
+            cmds.extend(labels);
+        }
+        self.results
+            .run(cmds, project.current_path.clone(), scheduler);
+    }
+
+    /// Pick up the result of a finished query, called from the UI loop
+    pub async fn update_results(&mut self) {
+        if self.results.update().await && !self.results.labels.is_empty() {
+            self.focus = ConsoleFocus::Results;
+        }
+    }
+
+    fn history_prev(&mut self) {
+        if self.history.is_empty() {
+            return;
+        }
+        let index = match self.history_cursor {
+            None => self.history.len() - 1,
+            Some(0) => 0,
+            Some(index) => index - 1,
+        };
+        self.recall_history(index);
+    }
+
+    fn history_next(&mut self) {
+        match self.history_cursor {
+            Some(index) if index + 1 < self.history.len() => self.recall_history(index + 1),
+            Some(_) => {
+                self.history_cursor = None;
+                self.input.clear();
+            }
+            None => {}
+        }
+    }
+
+    fn recall_history(&mut self, index: usize) {
+        let (kind, query) = self.history[index].clone();
+        self.kind = kind;
+        self.input = query;
+        self.history_cursor = Some(index);
+    }
+
+    fn add_to_history(&mut self, kind: QueryKind, query: String) {
+        self.history.retain(|(k, q)| !(*k == kind && *q == query));
+        self.history.push((kind, query));
+        if self.history.len() > MAX_HISTORY {
+            let excess = self.history.len() - MAX_HISTORY;
+            self.history.drain(..excess);
+        }
+        self.save_history();
+    }
+
+    fn history_path() -> Option<PathBuf> {
+        crate::state_dir().map(|dir| dir.join(HISTORY_FILE))
+    }
+
+    // History is stored one query per line as `<uquery|cquery>\t<expression>`
+    fn load_history() -> Vec<(QueryKind, String)> {
+        let Some(path) = Self::history_path() else {
+            return Vec::new();
+        };
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return Vec::new();
+        };
+
+        content
+            .lines()
+            .filter_map(|line| {
+                let (kind, query) = line.split_once('\t')?;
+                Some((QueryKind::from_command(kind)?, query.to_string()))
+            })
+            .collect()
+    }
+
+    fn save_history(&self) {
+        let Some(path) = Self::history_path() else {
+            return;
+        };
+
+        let content: String = self
+            .history
+            .iter()
+            .map(|(kind, query)| format!("{}\t{}\n", kind.command(), query))
+            .collect();
+
+        if let Some(parent) = path.parent()
+            && let Err(e) = std::fs::create_dir_all(parent)
+        {
+            warn!("Failed to create state directory {:?}: {}", parent, e);
+            return;
+        }
+        if let Err(e) = std::fs::write(&path, content) {
+            warn!("Failed to save query history to {:?}: {}", path, e);
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+
+use crate::buck::BuckProject;
+use crate::query_console::QueryResults;
+use crate::scheduler::Scheduler;
+
+/// Where to look for reverse dependencies
+#[derive(Debug, Clone, PartialEq)]
+pub enum RdepsUniverse {
+    // Every package of the cell containing the current directory
+    Cell,
+    // The subtree under the current directory
+    Directory,
+    // A user provided target pattern
+    Custom(String),
+}
+
+impl RdepsUniverse {
+    pub fn description(&self) -> &'static str {
+        match self {
+            RdepsUniverse::Cell => "current cell",
+            RdepsUniverse::Directory => "current directory",
+            RdepsUniverse::Custom(_) => "custom",
+        }
+    }
+
+    /// Target pattern for the universe, relative to the project's current directory
+    fn pattern(&self, project: &BuckProject) -> String {
+        match self {
+            RdepsUniverse::Cell => {
+                let cell = project
+                    .cell_for_path(&project.current_path)
+                    .unwrap_or_default();
+                format!("{}//...", cell)
+            }
+            RdepsUniverse::Directory => match project.package_for_path(&project.current_path) {
+                Some(package) if package.ends_with("//") => format!("{}...", package),
+                Some(package) => format!("{}/...", package),
+                None => "...".to_string(),
+            },
+            RdepsUniverse::Custom(pattern) => pattern.clone(),
+        }
+    }
+}
+
+/// "Who depends on this?" view, backed by `buck2 uquery "rdeps(<universe>, <label>, <depth>)"`
+/// (or cquery when a target platform is chosen)
+pub struct RdepsView {
+    pub active: bool,
+    pub target: String,
+    pub universe: RdepsUniverse,
+    pub universe_pattern: String,
+    pub depth: u32,
+    // Editing a custom universe pattern
+    pub editing_universe: bool,
+    pub universe_input: String,
+    pub results: QueryResults,
+}
+
+impl RdepsView {
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            target: String::new(),
+            universe: RdepsUniverse::Cell,
+            universe_pattern: String::new(),
+            depth: 1,
+            editing_universe: false,
+            universe_input: String::new(),
+            results: QueryResults::new(),
+        }
+    }
+
+    /// Open the view for `target`, keeping the universe chosen last time
+    pub fn open(&mut self, target: String, project: &BuckProject, scheduler: &Scheduler) {
+        self.active = true;
+        self.target = target;
+        self.depth = 1;
+        self.editing_universe = false;
+        self.run(project, scheduler);
+    }
+
+    pub fn close(&mut self) {
+        self.active = false;
+    }
+
+    /// Handle a key while the view is open.
+    /// Returns the label of the dependent the user picked, if any.
+    pub fn handle_key(
+        &mut self,
+        key: KeyEvent,
+        project: &BuckProject,
+        scheduler: &Scheduler,
+    ) -> Option<String> {
+        if self.editing_universe {
+            match key.code {
+                KeyCode::Esc => self.editing_universe = false,
+                KeyCode::Enter => {
+                    self.editing_universe = false;
+                    self.universe = RdepsUniverse::Custom(self.universe_input.trim().to_string());
+                    self.run(project, scheduler);
+                }
+                KeyCode::Backspace => {
+                    self.universe_input.pop();
+                }
+                KeyCode::Char(c) => self.universe_input.push(c),
+                _ => {}
+            }
+            return None;
+        }
+
+        match key.code {
+            KeyCode::Esc => self.close(),
+            KeyCode::Char('j') | KeyCode::Down => self.results.next(),
+            KeyCode::Char('k') | KeyCode::Up => self.results.prev(),
+            KeyCode::Char('+') | KeyCode::Char('=') | KeyCode::Char('l') => {
+                self.depth += 1;
+                self.run(project, scheduler);
+            }
+            KeyCode::Char('-') | KeyCode::Char('h') if self.depth > 1 => {
+                self.depth -= 1;
+                self.run(project, scheduler);
+            }
+            KeyCode::Char('u') => {
+                match self.universe {
+                    RdepsUniverse::Cell => {
+                        self.universe = RdepsUniverse::Directory;
+                        self.run(project, scheduler);
+                    }
+                    RdepsUniverse::Directory => {
+                        // Start from the current pattern so it can be tweaked
+                        self.universe_input = self.universe_pattern.clone();
+                        self.editing_universe = true;
+                    }
+                    RdepsUniverse::Custom(_) => {
+                        self.universe = RdepsUniverse::Cell;
+                        self.run(project, scheduler);
+                    }
+                }
+            }
+            KeyCode::Enter => return self.results.selected_label().map(|s| s.to_string()),
+            _ => {}
+        }
+        None
+    }
+
+    fn run(&mut self, project: &BuckProject, scheduler: &Scheduler) {
+        self.universe_pattern = self.universe.pattern(project);
+        let query = format!(
+            "rdeps({}, {}, {})",
+            self.universe_pattern, self.target, self.depth
+        );
+
+        self.results.run(
+            project.query_command(query),
+            project.current_path.clone(),
+            scheduler,
+        );
+    }
+
+    /// Pick up the query output, called from the UI loop
+    pub async fn update_results(&mut self) {
+        if self.results.update().await {
+            // rdeps() includes the target itself
+            let target = &self.target;
+            self.results.labels.retain(|label| label != target);
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use anyhow::Context;
+use anyhow::Result;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+const MAX_RECENT: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RecentAction {
+    Inspected,
+    Opened,
+    Built,
+    Tested,
+}
+
+impl RecentAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RecentAction::Inspected => "inspected",
+            RecentAction::Opened => "opened",
+            RecentAction::Built => "built",
+            RecentAction::Tested => "tested",
+        }
+    }
+
+    /// buck2 subcommand running the action, for the ones that run one
+    pub fn command(&self) -> Option<&'static str> {
+        match self {
+            RecentAction::Built => Some("build"),
+            RecentAction::Tested => Some("test"),
+            RecentAction::Inspected | RecentAction::Opened => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ActionResult {
+    // Nothing to report, e.g. for inspecting
+    Done,
+    Running,
+    // Was running when the app exited, the outcome is unknown
+    Interrupted,
+    Succeeded,
+    Failed(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentTarget {
+    pub label: String,
+    pub action: RecentAction,
+    pub result: ActionResult,
+    // Seconds since the epoch
+    pub time: u64,
+}
+
+// (labels, action, result) of finished build and test commands
+pub type ActionResults = Arc<Mutex<Vec<(Vec<String>, RecentAction, ActionResult)>>>;
+
+/// Most recently used targets of a project, newest first, saved under the state directory
+pub struct RecentTargets {
+    pub entries: Vec<RecentTarget>,
+    pub results: ActionResults,
+    file: Option<PathBuf>,
+}
+
+impl RecentTargets {
+    pub fn load(project_root: &Path) -> Self {
+        let file = crate::project_state_file(project_root, "recent.json");
+        let mut entries: Vec<RecentTarget> = file
+            .as_ref()
+            .and_then(|file| std::fs::read_to_string(file).ok())
+            .and_then(|content| match serde_json::from_str(&content) {
+                Ok(entries) => Some(entries),
+                Err(e) => {
+                    warn!("Ignoring unreadable recent targets: {}", e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+        for entry in &mut entries {
+            if entry.result == ActionResult::Running {
+                entry.result = ActionResult::Interrupted;
+            }
+        }
+        Self {
+            entries,
+            results: Arc::new(Mutex::new(Vec::new())),
+            file,
+        }
+    }
+
+    /// Move `label` to the front with its last action
+    pub fn record(&mut self, label: &str, action: RecentAction, result: ActionResult) {
+        self.record_all(&[label.to_string()], action, result);
+    }
+
+    /// Move `labels` to the front with the same action, saving once
+    pub fn record_all(&mut self, labels: &[String], action: RecentAction, result: ActionResult) {
+        for label in labels {
+            self.insert(label, action, result.clone());
+        }
+        self.entries.truncate(MAX_RECENT);
+        if let Err(e) = self.save() {
+            warn!("Failed to save recent targets: {}", e);
+        }
+    }
+
+    fn insert(&mut self, label: &str, action: RecentAction, result: ActionResult) {
+        let index = self.entries.iter().position(|entry| entry.label == label);
+        let entry = match index.map(|index| self.entries.remove(index)) {
+            // Inspecting or opening a target doesn't hide its build or test still running
+            Some(entry) if entry.result == ActionResult::Running && action.command().is_none() => {
+                entry
+            }
+            _ => RecentTarget {
+                label: label.to_string(),
+                action,
+                result,
+                time: now(),
+            },
+        };
+        self.entries.insert(0, entry);
+    }
+
+    /// The build or test of `label` still running, if any
+    pub fn running_action(&self, label: &str) -> Option<RecentAction> {
+        self.entries
+            .iter()
+            .find(|entry| entry.label == label && entry.result == ActionResult::Running)
+            .map(|entry| entry.action)
+    }
+
+    /// Record the outcome of finished build and test commands, called from the UI loop.
+    /// Returns them to report in the status bar.
+    pub async fn update_results(&mut self) -> Vec<(Vec<String>, RecentAction, ActionResult)> {
+        let results = std::mem::take(&mut *self.results.lock().await);
+        for (labels, action, result) in &results {
+            self.record_all(labels, *action, result.clone());
+        }
+        results
+    }
+
+    fn save(&self) -> Result<()> {
+        let file = self
+            .file
+            .as_ref()
+            .context("Failed to determine the state directory")?;
+        if let Some(dir) = file.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        std::fs::write(file, serde_json::to_string_pretty(&self.entries)?)
+            .with_context(|| format!("Failed to write {}", file.display()))
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// How long ago `time` was, e.g. `5m ago`
+pub fn format_age(time: u64) -> String {
+    let seconds = now().saturating_sub(time);
+    match seconds {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{}m ago", seconds / 60),
+        3600..86400 => format!("{}h ago", seconds / 3600),
+        _ => format!("{}d ago", seconds / 86400),
+    }
+}
+
+/// Quick access popup over the recent targets
+pub struct RecentView {
+    pub active: bool,
+    pub selected: usize,
+}
+
+impl RecentView {
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            selected: 0,
+        }
+    }
+
+    pub fn open(&mut self) {
+        self.active = true;
+        self.selected = 0;
+    }
+
+    pub fn close(&mut self) {
+        self.active = false;
+    }
+
+    /// Handle a key while the popup is open. Returns the label of the picked target, if any.
+    pub fn handle_key(&mut self, key: KeyEvent, recent: &RecentTargets) -> Option<String> {
+        let count = recent.entries.len();
+        match key.code {
+            KeyCode::Esc => self.close(),
+            KeyCode::Char('j') | KeyCode::Down if count > 0 => {
+                self.selected = (self.selected + 1) % count;
+            }
+            KeyCode::Char('k') | KeyCode::Up if count > 0 => {
+                self.selected = (self.selected + count - 1) % count;
+            }
+            KeyCode::Enter => {
+                return recent
+                    .entries
+                    .get(self.selected)
+                    .map(|entry| entry.label.clone());
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recent() -> RecentTargets {
+        RecentTargets {
+            entries: Vec::new(),
+            results: Arc::new(Mutex::new(Vec::new())),
+            file: None,
+        }
+    }
+
+    fn entries(recent: &RecentTargets) -> Vec<(&str, RecentAction, ActionResult)> {
+        recent
+            .entries
+            .iter()
+            .map(|e| (e.label.as_str(), e.action, e.result.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn inspecting_keeps_a_running_build() {
+        let mut recent = recent();
+        let labels = vec!["root//a:a".to_string(), "root//b:b".to_string()];
+        recent.record_all(&labels, RecentAction::Built, ActionResult::Running);
+        recent.record("root//a:a", RecentAction::Inspected, ActionResult::Done);
+
+        assert_eq!(
+            entries(&recent),
+            vec![
+                ("root//a:a", RecentAction::Built, ActionResult::Running),
+                ("root//b:b", RecentAction::Built, ActionResult::Running),
+            ]
+        );
+        assert_eq!(
+            recent.running_action("root//a:a"),
+            Some(RecentAction::Built)
+        );
+
+        recent.record_all(&labels, RecentAction::Built, ActionResult::Succeeded);
+        recent.record("root//a:a", RecentAction::Inspected, ActionResult::Done);
+        assert_eq!(
+            entries(&recent),
+            vec![
+                ("root//a:a", RecentAction::Inspected, ActionResult::Done),
+                ("root//b:b", RecentAction::Built, ActionResult::Succeeded),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_the_most_recent_entries() {
+        let mut recent = recent();
+        let labels: Vec<String> = (0..MAX_RECENT + 5)
+            .map(|i| format!("root//pkg:t{}", i))
+            .collect();
+        recent.record_all(&labels, RecentAction::Tested, ActionResult::Running);
+
+        assert_eq!(recent.entries.len(), MAX_RECENT);
+        assert_eq!(recent.entries[0].label, labels[MAX_RECENT + 4]);
+    }
+}
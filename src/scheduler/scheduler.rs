@@ -121,16 +121,28 @@ impl Scheduler {
             return Ok(());
         }
 
+        let task_on_success = task.take_task_on_success();
+        let task_on_failure = task.take_task_on_failure();
+
         let (program, args) = task.cmds.split_first().unwrap();
-        let mut child = tokio::process::Command::new(program)
+        let mut child = match tokio::process::Command::new(program)
             .args(args)
             .current_dir(task.current_dir.clone())
             .stdin(std::process::Stdio::null()) // Don't inherit stdin
             .stdout(std::process::Stdio::piped()) // Capture stdout
             .stderr(std::process::Stdio::piped()) // Capture stderr
-            .spawn()?;
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) => {
+                // e.g. buck2 is not in PATH, report it instead of bringing the worker down
+                if let Some(task_on_failure) = task_on_failure {
+                    task_on_failure(format!("Failed to run {}: {}", program, e)).await;
+                }
+                return Ok(());
+            }
+        };
 
-        let task_on_success = task.take_task_on_success();
         let cancel_token = task.cancel_token.clone();
         let hooks = task.hooks.clone();
 
@@ -138,7 +150,7 @@ impl Scheduler {
             let stdout = child.stdout.take().unwrap();
             let stderr = child.stderr.take().unwrap();
 
-            let (stdout, _stderr, status) = tokio::select! {
+            let (stdout, stderr, status) = tokio::select! {
                 result = async {
                     let (stdout_result, stderr_result, exit_status) = tokio::join!(
                         async {
@@ -177,6 +189,9 @@ impl Scheduler {
                     task_on_success(stdout).await;
                     hooks.run_all().await;
                 }
+            } else if let Some(task_on_failure) = task_on_failure {
+                task_on_failure(stderr).await;
+                hooks.run_all().await;
             }
 
             Ok(())
@@ -26,6 +26,10 @@ pub enum Priority {
 pub type TaskOnSuccess =
     Box<dyn FnOnce(String) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
 
+// Called with stderr (or the spawn error) when the command does not exit successfully
+pub type TaskOnFailure =
+    Box<dyn FnOnce(String) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
 // Task that runs cmds
 pub struct Task {
     pub id: TaskId,
@@ -33,6 +37,7 @@ pub struct Task {
     pub priority: Priority,
     pub hooks: Arc<Hooks>,
     task_on_success: Option<TaskOnSuccess>,
+    task_on_failure: Option<TaskOnFailure>,
     pub(crate) cmds: Vec<String>,
     pub(crate) current_dir: PathBuf,
     pub cancel_token: CancellationToken,
@@ -51,12 +56,18 @@ impl Task {
             priority,
             hooks: Arc::new(Hooks::new()),
             task_on_success: Some(task_on_success),
+            task_on_failure: None,
             cmds,
             current_dir,
             cancel_token: CancellationToken::new(),
         }
     }
 
+    pub fn with_on_failure(mut self, task_on_failure: TaskOnFailure) -> Self {
+        self.task_on_failure = Some(task_on_failure);
+        self
+    }
+
     pub fn dispatch(&mut self) {
         self.stage = TaskStage::Dispatched;
     }
@@ -73,6 +84,10 @@ impl Task {
         self.task_on_success.take()
     }
 
+    pub(crate) fn take_task_on_failure(&mut self) -> Option<TaskOnFailure> {
+        self.task_on_failure.take()
+    }
+
     pub fn cancel(&self) {
         self.cancel_token.cancel();
     }
@@ -89,6 +104,7 @@ impl std::fmt::Debug for Task {
             .field("stage", &self.stage)
             .field("priority", &self.priority)
             .field("has_task_on_success", &self.task_on_success.is_some())
+            .field("has_task_on_failure", &self.task_on_failure.is_some())
             .finish()
     }
 }
@@ -0,0 +1,45 @@
+use anyhow::Context;
+use anyhow::Result;
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::Path;
+use tracing::warn;
+
+use crate::app::SearchPane;
+use crate::jump_list::Location;
+use crate::ui::Pane;
+use crate::ui::PaneGroup;
+
+const SESSION_FILE: &str = "session.json";
+
+/// Where the user left off, saved on exit and restored on the next launch in the same project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub location: Location,
+    pub pane: Pane,
+    pub group: PaneGroup,
+    pub search_query: String,
+    pub search_pane: SearchPane,
+}
+
+impl Session {
+    /// The session saved for the project at `project_root`, if any
+    pub fn load(project_root: &Path) -> Option<Self> {
+        let file = crate::project_state_file(project_root, SESSION_FILE)?;
+        let content = std::fs::read_to_string(&file).ok()?;
+        serde_json::from_str(&content)
+            .inspect_err(|e| warn!("Ignoring unreadable session {}: {}", file.display(), e))
+            .ok()
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<()> {
+        let file = crate::project_state_file(project_root, SESSION_FILE)
+            .context("Failed to determine the state directory")?;
+        if let Some(dir) = file.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        std::fs::write(&file, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", file.display()))
+    }
+}
@@ -0,0 +1,235 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::buck::BuckTarget;
+
+// Attributes listing the files a target compiles or ships
+const SOURCE_ATTRIBUTES: [&str; 5] = ["srcs", "headers", "exported_headers", "resources", "data"];
+const PREVIEW_LINES: usize = 200;
+
+/// A file referenced by one of the source attributes of a target
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    pub attribute: &'static str,
+    // Relative to the package, or as written in the attribute for files outside of it
+    pub name: String,
+    pub path: PathBuf,
+    // None when the file doesn't exist
+    pub size: Option<u64>,
+}
+
+/// Collect the files of the source attributes. Cell-qualified paths (`root//foo/bar.rs`, as
+/// `buck2 targets -A` prints them) are resolved through `cells`. Labels of generated sources
+/// (`:gen`, `//foo:bar`) are skipped, they don't exist in the source tree.
+pub fn source_files(target: &BuckTarget, cells: &HashMap<String, PathBuf>) -> Vec<SourceFile> {
+    let mut files = Vec::new();
+    for attribute in SOURCE_ATTRIBUTES {
+        let Some(value) = target.attributes.get(attribute) else {
+            continue;
+        };
+
+        let mut names = Vec::new();
+        collect_paths(value, &mut names);
+        for name in names {
+            if name.contains(':') {
+                continue;
+            }
+            let path = match name.split_once("//") {
+                Some((cell, path)) => match cells.get(cell) {
+                    Some(cell_path) => cell_path.join(path),
+                    None => continue,
+                },
+                None => target.path.join(&name),
+            };
+            let name = path
+                .strip_prefix(&target.path)
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or(name);
+            let size = std::fs::metadata(&path).ok().map(|m| m.len());
+            files.push(SourceFile {
+                attribute,
+                name,
+                path,
+                size,
+            });
+        }
+    }
+    files
+}
+
+/// Strings of lists and dict values, e.g. `srcs = {"dest.h": "src.h"}` gives `src.h`
+fn collect_paths(value: &Value, names: &mut Vec<String>) {
+    match value {
+        Value::String(s) => names.push(s.clone()),
+        Value::Array(values) => values.iter().for_each(|v| collect_paths(v, names)),
+        Value::Object(map) => map.values().for_each(|v| collect_paths(v, names)),
+        _ => {}
+    }
+}
+
+/// Human readable file size
+pub fn format_size(size: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = size as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", size)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Sources tab of the Details pane: the file list of the selected target, the cursor and
+/// the preview of the file under it. Read again only when another target or file gets selected.
+#[derive(Debug, Default)]
+pub struct SourcesState {
+    pub target: String,
+    pub files: Vec<SourceFile>,
+    pub cursor: usize,
+    pub preview: Vec<String>,
+    preview_path: Option<PathBuf>,
+}
+
+impl SourcesState {
+    /// Follow the selected target, called from the UI loop
+    pub fn update(&mut self, target: Option<&BuckTarget>, cells: &HashMap<String, PathBuf>) {
+        let label = target
+            .map(|t| t.full_target_label_name.as_str())
+            .unwrap_or_default();
+        if self.target == label {
+            return;
+        }
+
+        self.target = label.to_string();
+        self.files = target.map(|t| source_files(t, cells)).unwrap_or_default();
+        self.cursor = 0;
+        self.load_preview();
+    }
+
+    fn load_preview(&mut self) {
+        self.preview_path = self.selected().map(|f| f.path.clone());
+        self.preview = self
+            .preview_path
+            .as_deref()
+            .map(read_preview)
+            .unwrap_or_default();
+    }
+
+    pub fn selected(&self) -> Option<&SourceFile> {
+        self.files.get(self.cursor)
+    }
+
+    pub fn next(&mut self) {
+        if !self.files.is_empty() {
+            self.cursor = (self.cursor + 1) % self.files.len();
+            self.load_preview();
+        }
+    }
+
+    pub fn prev(&mut self) {
+        if !self.files.is_empty() {
+            self.cursor = (self.cursor + self.files.len() - 1) % self.files.len();
+            self.load_preview();
+        }
+    }
+}
+
+fn read_preview(path: &Path) -> Vec<String> {
+    let Ok(file) = std::fs::File::open(path) else {
+        return vec!["File not found".to_string()];
+    };
+    let mut lines = Vec::new();
+    for line in BufReader::new(file).lines().take(PREVIEW_LINES) {
+        match line {
+            Ok(line) => lines.push(line.replace('\t', "    ")),
+            Err(_) => return vec!["Binary file".to_string()],
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(dir: &Path, attributes: serde_json::Value) -> BuckTarget {
+        let mut json = serde_json::json!({"name": "lib", "buck.type": "rust_library"});
+        json.as_object_mut()
+            .unwrap()
+            .extend(attributes.as_object().unwrap().clone());
+        BuckTarget::from_json_value(&json, dir)
+    }
+
+    fn names(files: &[SourceFile]) -> Vec<(&str, &str)> {
+        files
+            .iter()
+            .map(|f| (f.attribute, f.name.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn resolves_cell_qualified_paths() {
+        let cells = HashMap::from([
+            ("root".to_string(), PathBuf::from("/repo")),
+            ("prelude".to_string(), PathBuf::from("/repo/prelude")),
+        ]);
+        let target = target(
+            Path::new("/repo/pkg"),
+            serde_json::json!({
+                "srcs": ["root//pkg/src/lib.rs", "root//other/shared.rs"],
+                "resources": ["prelude//tools/tool.py"]
+            }),
+        );
+        let files = source_files(&target, &cells);
+        assert_eq!(
+            names(&files),
+            vec![
+                ("srcs", "src/lib.rs"),
+                ("srcs", "root//other/shared.rs"),
+                ("resources", "prelude//tools/tool.py"),
+            ]
+        );
+        assert_eq!(files[0].path, PathBuf::from("/repo/pkg/src/lib.rs"));
+        assert_eq!(files[1].path, PathBuf::from("/repo/other/shared.rs"));
+        assert_eq!(files[2].path, PathBuf::from("/repo/prelude/tools/tool.py"));
+        assert!(files.iter().all(|f| f.size.is_none()));
+    }
+
+    #[test]
+    fn skips_labels_and_unknown_cells() {
+        let cells = HashMap::from([("root".to_string(), PathBuf::from("/repo"))]);
+        let target = target(
+            Path::new("/repo/pkg"),
+            serde_json::json!({
+                "srcs": [":gen", "//pkg:gen", "root//pkg:gen", "other//pkg/a.rs", "b.rs"],
+                "exported_headers": {"public/c.h": "root//pkg/c.h"}
+            }),
+        );
+        assert_eq!(
+            names(&source_files(&target, &cells)),
+            vec![("srcs", "b.rs"), ("exported_headers", "c.h")]
+        );
+    }
+
+    #[test]
+    fn reads_the_size_of_existing_files() {
+        let dir = std::env::temp_dir().join(format!("buck-tui-sources-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("lib.rs"), "fn main() {}\n").unwrap();
+
+        let target = target(&dir, serde_json::json!({"srcs": ["lib.rs", "missing.rs"]}));
+        let files = source_files(&target, &HashMap::new());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(files[0].size, Some(13));
+        assert_eq!(files[1].size, None);
+    }
+}
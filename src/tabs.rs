@@ -0,0 +1,162 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::app::SearchState;
+use crate::jump_list::JumpList;
+use crate::jump_list::Location;
+use crate::ui::Pane;
+use crate::ui::PaneGroup;
+
+/// View state of a tab that is not shown. The shown tab lives in the project, UI and search
+/// state, which share the loaded targets and the scheduler between all tabs.
+pub struct Tab {
+    pub location: Location,
+    pub jump_list: JumpList,
+    pub target_filter: Option<String>,
+    pub target_selection: Vec<String>,
+    pub visual_anchor: Option<(PathBuf, usize)>,
+    pub pane: Pane,
+    pub group: PaneGroup,
+    pub search_state: SearchState,
+}
+
+/// Tabs in order, `active` being the shown one. Its state is not in `parked`, so the others
+/// are `parked[..active]` before it and `parked[active..]` after it.
+pub struct Tabs {
+    parked: Vec<Tab>,
+    pub active: usize,
+}
+
+impl Tabs {
+    pub fn new() -> Self {
+        Self {
+            parked: Vec::new(),
+            active: 0,
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.parked.len() + 1
+    }
+
+    /// Park the shown tab, a new one is shown right after it
+    pub fn open(&mut self, current: Tab) {
+        self.parked.insert(self.active, current);
+        self.active += 1;
+    }
+
+    /// Park the shown tab and take out the one at `index` to show it. `index` must be another tab.
+    pub fn switch(&mut self, index: usize, current: Tab) -> Tab {
+        self.parked.insert(self.active, current);
+        self.active = index;
+        self.parked.remove(index)
+    }
+
+    /// Drop the shown tab and take out the one to show instead, the next one or the last one
+    /// when it was the last. None when it is the only tab.
+    pub fn close(&mut self) -> Option<Tab> {
+        if self.parked.is_empty() {
+            return None;
+        }
+        self.active = self.active.min(self.parked.len() - 1);
+        Some(self.parked.remove(self.active))
+    }
+
+    /// Directory names of the tabs in order, `current_path` being the one of the shown tab
+    pub fn titles(&self, current_path: &Path) -> Vec<String> {
+        let (before, after) = self.parked.split_at(self.active);
+        before
+            .iter()
+            .map(|tab| tab.location.current_path.as_path())
+            .chain(std::iter::once(current_path))
+            .chain(after.iter().map(|tab| tab.location.current_path.as_path()))
+            .map(|path| {
+                path.file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path.display().to_string())
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tab(name: &str) -> Tab {
+        Tab {
+            location: Location {
+                current_path: PathBuf::from("/repo").join(name),
+                selected_directory: PathBuf::from("/repo").join(name),
+                target: None,
+            },
+            jump_list: JumpList::default(),
+            target_filter: None,
+            target_selection: Vec::new(),
+            visual_anchor: None,
+            pane: Pane::Targets,
+            group: PaneGroup::Inspector,
+            search_state: SearchState::new(),
+        }
+    }
+
+    fn name(tab: &Tab) -> String {
+        tab.location.current_path.display().to_string()
+    }
+
+    #[test]
+    fn open_shows_the_new_tab_after_the_current_one() {
+        let mut tabs = Tabs::new();
+        tabs.open(tab("a"));
+        tabs.open(tab("b"));
+        assert_eq!(tabs.count(), 3);
+        assert_eq!(tabs.active, 2);
+        assert_eq!(tabs.titles(Path::new("/repo/c")), vec!["a", "b", "c"]);
+
+        // Opening from the first tab inserts right after it
+        let shown = tabs.switch(0, tab("c"));
+        assert_eq!(name(&shown), "/repo/a");
+        tabs.open(shown);
+        assert_eq!(tabs.active, 1);
+        assert_eq!(tabs.titles(Path::new("/repo/d")), vec!["a", "d", "b", "c"]);
+    }
+
+    #[test]
+    fn switch_parks_the_shown_tab_in_place() {
+        let mut tabs = Tabs::new();
+        tabs.open(tab("a"));
+        tabs.open(tab("b"));
+
+        let shown = tabs.switch(0, tab("c"));
+        assert_eq!(name(&shown), "/repo/a");
+        assert_eq!(tabs.active, 0);
+        assert_eq!(tabs.titles(Path::new("/repo/a")), vec!["a", "b", "c"]);
+
+        let shown = tabs.switch(2, shown);
+        assert_eq!(name(&shown), "/repo/c");
+        assert_eq!(tabs.active, 2);
+        assert_eq!(tabs.titles(Path::new("/repo/c")), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn close_shows_the_next_tab_or_the_last_one() {
+        let mut tabs = Tabs::new();
+        tabs.open(tab("a"));
+        tabs.open(tab("b"));
+        let shown = tabs.switch(1, tab("c"));
+        assert_eq!(name(&shown), "/repo/b");
+
+        let shown = tabs.close().unwrap();
+        assert_eq!(name(&shown), "/repo/c");
+        assert_eq!(tabs.active, 1);
+        assert_eq!(tabs.titles(Path::new("/repo/c")), vec!["a", "c"]);
+
+        let shown = tabs.close().unwrap();
+        assert_eq!(name(&shown), "/repo/a");
+        assert_eq!(tabs.active, 0);
+        assert_eq!(tabs.count(), 1);
+
+        assert!(tabs.close().is_none());
+        assert_eq!(tabs.active, 0);
+    }
+}
@@ -6,6 +6,7 @@ use ratatui::layout::Rect;
 use ratatui::style::Color;
 use ratatui::style::Modifier;
 use ratatui::style::Style;
+use ratatui::symbols::Marker;
 use ratatui::text::Line;
 use ratatui::text::Span;
 use ratatui::widgets::Block;
@@ -16,21 +17,89 @@ use ratatui::widgets::ListItem;
 use ratatui::widgets::ListState;
 use ratatui::widgets::Paragraph;
 use ratatui::widgets::Wrap;
+use ratatui::widgets::canvas::Canvas;
+use ratatui::widgets::canvas::Line as CanvasLine;
+use ratatui::widgets::canvas::Rectangle;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
 
 use crate::app::SearchState;
+use crate::attributes::AttributeTreeState;
+use crate::attributes::attribute_rows;
+use crate::bookmarks::Bookmarks;
+use crate::bookmarks::BookmarksView;
 use crate::buck::BuckProject;
 use crate::buck::BuckTarget;
+use crate::buck::ConfiguredTarget;
+use crate::cells::CellBrowser;
+use crate::changes::ChangedTargetsView;
+use crate::dep_path::DepPathFocus;
+use crate::dep_path::DepPathView;
+use crate::dep_path::PathRow;
+use crate::dep_tree::DepTree;
+use crate::dep_tree::DepsState;
+use crate::dep_tree::NodeMark;
+use crate::export::ExportDialog;
+use crate::export::ExportField;
+use crate::export::ExportScope;
+use crate::filter::TargetFilter;
+use crate::goto::GotoPrompt;
+use crate::graph_view::GraphView;
+use crate::jump_list::JumpListView;
+use crate::owner::OwnerLookup;
+use crate::platform::PlatformChoice;
+use crate::platform::PlatformPicker;
+use crate::query_console::ConsoleFocus;
+use crate::query_console::QueryConsole;
+use crate::query_console::QueryResults;
+use crate::rdeps::RdepsView;
+use crate::recent::ActionResult;
+use crate::recent::RecentTargets;
+use crate::recent::RecentView;
+use crate::recent::format_age;
+use crate::sources::SourcesState;
+use crate::sources::format_size;
+use crate::tabs::Tabs;
+
+/// Transient message shown in the status bar until the next key press
+#[derive(Debug, Clone)]
+pub enum StatusMessage {
+    Info(String),
+    Error(String),
+}
 
 pub struct UI {
     pub current_pane: Pane,
     pub current_group: PaneGroup,
+    pub status_message: Option<StatusMessage>,
+    pub attribute_tree: AttributeTreeState,
+    pub details_tab: DetailsTab,
+    pub sources: SourcesState,
     parent_list_state: ListState,
     current_list_state: ListState,
     targets_list_state: ListState,
     actions_list_state: ListState,
+    query_results_list_state: ListState,
+    dep_tree_list_state: ListState,
+    dep_path_list_state: ListState,
+    platform_list_state: ListState,
+    cell_list_state: ListState,
+    jump_list_state: ListState,
+    bookmarks_list_state: ListState,
+    recent_list_state: ListState,
+    sources_list_state: ListState,
 }
 
+/// Tabs of the Details pane
 #[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DetailsTab {
+    Info,
+    Sources,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Pane {
     ParentDirectory,
     CurrentDirectory,
@@ -39,7 +108,7 @@ pub enum Pane {
     Details,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum PaneGroup {
     Explorer,  // Parent + Current directory panes
     Inspector, // Targets + Details panes
@@ -50,25 +119,46 @@ impl UI {
         Self {
             current_pane: Pane::CurrentDirectory,
             current_group: PaneGroup::Explorer,
+            status_message: None,
+            attribute_tree: AttributeTreeState::default(),
+            details_tab: DetailsTab::Info,
+            sources: SourcesState::default(),
             parent_list_state: ListState::default(),
             current_list_state: ListState::default(),
             targets_list_state: ListState::default(),
             actions_list_state: ListState::default(),
+            query_results_list_state: ListState::default(),
+            dep_tree_list_state: ListState::default(),
+            dep_path_list_state: ListState::default(),
+            platform_list_state: ListState::default(),
+            cell_list_state: ListState::default(),
+            jump_list_state: ListState::default(),
+            bookmarks_list_state: ListState::default(),
+            recent_list_state: ListState::default(),
+            sources_list_state: ListState::default(),
         }
     }
 
-    pub fn draw(&mut self, f: &mut Frame, project: &BuckProject, search_state: &SearchState) {
+    pub fn draw(
+        &mut self,
+        f: &mut Frame,
+        project: &BuckProject,
+        search_state: &SearchState,
+        tabs: &Tabs,
+    ) {
         // Split main area into top path bar and main content
         let main_chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(1), // Path bar (no border, just text)
                 Constraint::Min(0),    // Main content
+                Constraint::Length(1), // Status bar
             ])
             .split(f.area());
 
         // Draw path bar at the top
-        self.draw_path_bar(f, main_chunks[0], project);
+        self.draw_path_bar(f, main_chunks[0], project, tabs);
+        self.draw_status_bar(f, main_chunks[2], project);
 
         // Split main content into four horizontal panes
         let content_chunks = Layout::default()
@@ -94,7 +184,11 @@ impl UI {
         self.draw_current_directory(f, content_chunks[1], project, search_state);
         self.draw_targets(f, targets_chunks[0], project, search_state);
         self.draw_selected_directory(f, targets_chunks[1], project);
-        self.draw_details(f, content_chunks[3], project);
+        if self.details_tab == DetailsTab::Sources {
+            self.draw_sources(f, content_chunks[3]);
+        } else {
+            self.draw_details(f, content_chunks[3], project);
+        }
 
         // Draw search popup if active
         if search_state.active {
@@ -112,6 +206,8 @@ impl UI {
                 let is_current = dir.path == project.current_path;
                 let style = if is_current {
                     Style::default().bg(Color::Blue).fg(Color::White)
+                } else if project.is_highlighted(&dir.path) {
+                    Style::default().fg(Color::Magenta)
                 } else {
                     Style::default()
                 };
@@ -123,14 +219,15 @@ impl UI {
                     .to_string_lossy();
 
                 let buck_indicator = if dir.has_buck_file { "📦" } else { "📁" };
-                let text = format!("{} {}", buck_indicator, display_path);
+                let mut spans = vec![Span::raw(format!("{} {}", buck_indicator, display_path))];
+                spans.extend(Self::cell_marker(project, &dir.path));
 
                 // Update list state to select current directory
                 if is_current {
                     self.parent_list_state.select(Some(idx));
                 }
 
-                ListItem::new(text).style(style)
+                ListItem::new(Line::from(spans)).style(style)
             })
             .collect();
 
@@ -177,6 +274,8 @@ impl UI {
                 let is_selected = dir.path == project.selected_directory;
                 let style = if is_selected {
                     Style::default().bg(Color::Blue).fg(Color::White)
+                } else if project.is_highlighted(&dir.path) {
+                    Style::default().fg(Color::Magenta)
                 } else {
                     Style::default()
                 };
@@ -221,11 +320,14 @@ impl UI {
                     let mut spans = vec![Span::raw(format!("{} ", buck_indicator))];
                     spans.extend(Self::highlight_matches(&display_path, &search_state.query, is_current_match));
                     spans.push(Span::raw(format!(" ({})", target_count)));
+                    spans.extend(Self::cell_marker(project, &dir.path));
                     ListItem::new(Line::from(spans)).style(style)
                 } else {
                     // No highlighting, use plain text
                     let text = format!("{} {} ({})", buck_indicator, display_path, target_count);
-                    ListItem::new(text).style(style)
+                    let mut spans = vec![Span::raw(text)];
+                    spans.extend(Self::cell_marker(project, &dir.path));
+                    ListItem::new(Line::from(spans)).style(style)
                 };
 
                 item
@@ -263,7 +365,10 @@ impl UI {
         // Get contents of the selected directory from current directory pane
         let selected_dirs = if project.selected_directory != project.current_path {
             // Show contents of the selected directory
-            let selected_ui_dir = crate::buck::UICurrentDirectory::new(&project.selected_directory);
+            let selected_ui_dir = crate::buck::UICurrentDirectory::new(
+                &project.selected_directory,
+                &project.build_files,
+            );
             selected_ui_dir.sub_directories
         } else {
             // If current directory is selected, show empty or current contents
@@ -299,8 +404,15 @@ impl UI {
 
                 let buck_indicator = if dir.has_buck_file { "📦" } else { "📁" };
                 let text = format!("{} {} ({})", buck_indicator, display_path, target_count);
+                let mut spans = vec![Span::raw(text)];
+                spans.extend(Self::cell_marker(project, &dir.path));
+                let style = if project.is_highlighted(&dir.path) {
+                    Style::default().fg(Color::Magenta)
+                } else {
+                    Style::default()
+                };
 
-                ListItem::new(text)
+                ListItem::new(Line::from(spans)).style(style)
             })
             .collect();
 
@@ -322,6 +434,66 @@ impl UI {
         // Highlight matches even if popup is closed
         let should_highlight = !search_state.query.is_empty()
             && matches!(search_state.searching_in_pane, crate::app::SearchPane::Targets);
+        // Field predicates (e.g. `rule:rust_library`) don't map to a substring of the title,
+        // so structured queries highlight the whole title of matching targets instead
+        let structured_query =
+            should_highlight && TargetFilter::parse(&search_state.query).is_structured();
+
+        let block_style = if self.current_pane == Pane::Targets {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        };
+
+        let package_name = project
+            .get_selected_buck_package_name()
+            .map(|name| format!("{}: ", name))
+            .unwrap_or("No package selected".to_string());
+
+        // TODO: use package path like fbcode//buck2/app:
+        let title = match &project.target_filter {
+            Some(filter) => format!("Targets ({}) [filter: {}]", package_name, filter),
+            None => format!("Targets ({})", package_name),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(block_style);
+
+        // The build file failed to evaluate, show why instead of an empty list
+        if let Some(error) = project
+            .get_selected_directory()
+            .filter(|dir| !dir.targets_loading)
+            .and_then(|dir| dir.load_error.as_ref())
+        {
+            let mut lines = vec![
+                Line::from(Span::styled(
+                    "Failed to load targets",
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                )),
+                Line::from(Span::styled(
+                    error.message.as_str(),
+                    Style::default().fg(Color::Red),
+                )),
+            ];
+            if let Some(file) = &error.file {
+                let location = match error.line {
+                    Some(line) => format!("{}:{}", file.display(), line),
+                    None => file.display().to_string(),
+                };
+                lines.push(Line::from(""));
+                lines.push(Line::from(vec![
+                    Span::styled("at ", Style::default().fg(Color::Gray)),
+                    Span::raw(location),
+                    Span::styled(" (o: open)", Style::default().fg(Color::Gray)),
+                ]));
+            }
+            let paragraph = Paragraph::new(lines)
+                .block(block)
+                .wrap(Wrap { trim: false });
+            f.render_widget(paragraph, area);
+            return;
+        }
 
         let targets: Vec<ListItem> = if let Some(selected_dir) = project.get_selected_directory() {
             if selected_dir.targets_loading {
@@ -347,23 +519,49 @@ impl UI {
                         );
 
                         let target_name = target.display_title();
+                        let marker = if project.is_target_selected(i) {
+                            Span::styled("●", Style::default().fg(Color::Green))
+                        } else {
+                            Span::raw(" ")
+                        };
 
                         // Determine if this is the current match
                         let is_current_match = should_highlight
                             && search_state.matches.get(search_state.current_match_idx) == Some(&i);
 
                         // Create the line with highlighting if needed
-                        let text = if should_highlight && target_name.to_lowercase().contains(&search_state.query.to_lowercase()) {
-                            let mut spans = vec![
-                                Span::raw(" "),
+                        let text = if structured_query && search_state.matches.contains(&i) {
+                            let match_style = if is_current_match {
+                                Style::default()
+                                    .add_modifier(Modifier::UNDERLINED)
+                                    .bg(Color::Yellow)
+                                    .fg(Color::Black)
+                            } else {
+                                Style::default()
+                                    .add_modifier(Modifier::UNDERLINED)
+                                    .fg(Color::Yellow)
+                            };
+                            Line::from(vec![
+                                marker,
                                 icon_span,
                                 Span::raw(" "),
-                            ];
-                            spans.extend(Self::highlight_matches(&target_name, &search_state.query, is_current_match));
+                                Span::styled(target_name, match_style),
+                            ])
+                        } else if should_highlight
+                            && target_name
+                                .to_lowercase()
+                                .contains(&search_state.query.to_lowercase())
+                        {
+                            let mut spans = vec![marker, icon_span, Span::raw(" ")];
+                            spans.extend(Self::highlight_matches(
+                                &target_name,
+                                &search_state.query,
+                                is_current_match,
+                            ));
                             Line::from(spans)
                         } else {
                             Line::from(vec![
-                                Span::raw(" "),
+                                marker,
                                 icon_span,
                                 Span::raw(" "),
                                 Span::raw(target_name),
@@ -381,27 +579,8 @@ impl UI {
         // Update list state to track selected target
         self.targets_list_state.select(Some(project.selected_target));
 
-        let block_style = if self.current_pane == Pane::Targets {
-            Style::default().fg(Color::Yellow)
-        } else {
-            Style::default()
-        };
-
-        let package_name = project
-            .get_selected_buck_package_name()
-            .map(|name| format!("{}: ", name))
-            .unwrap_or("No package selected".to_string());
-
-        // TODO: use package path like fbcode//buck2/app:
-        let title = format!("Targets ({})", package_name);
-
         let targets_list = List::new(targets)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title(title)
-                    .border_style(block_style),
-            )
+            .block(block)
             .highlight_style(Style::default().add_modifier(Modifier::BOLD));
 
         f.render_stateful_widget(targets_list, area, &mut self.targets_list_state);
@@ -414,8 +593,32 @@ impl UI {
             Style::default()
         };
 
+        let mut scroll = 0;
         let details_text = if let Some(target) = project.get_selected_target() {
-            self.format_target_details(target)
+            let configured = project.target_platform.as_ref().map(|_| {
+                project
+                    .configured_targets
+                    .get(&target.full_target_label_name)
+            });
+            let deps = project.displayed_deps(target);
+            let (mut lines, label_cursor_line) =
+                self.format_target_details(target, configured, deps);
+
+            let label_count = target.visibility.len() + deps.len();
+            let attribute_lines = self.format_attributes(target, label_count);
+            if self.current_pane == Pane::Details {
+                // Keep the entry under the cursor in view
+                let cursor_line = label_cursor_line.unwrap_or_else(|| {
+                    lines.len()
+                        + 2
+                        + self
+                            .attribute_tree_cursor(target)
+                            .saturating_sub(label_count)
+                });
+                scroll = cursor_line.saturating_sub(area.height.saturating_sub(4) as usize);
+            }
+            lines.extend(attribute_lines);
+            lines
         } else {
             vec![Line::from("No target selected")]
         };
@@ -424,17 +627,38 @@ impl UI {
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title("Details")
+                    .title(Self::details_title(DetailsTab::Info))
                     .border_style(block_style),
             )
-            .wrap(Wrap { trim: true });
+            .wrap(Wrap { trim: true })
+            .scroll((scroll as u16, 0));
 
         f.render_widget(details, area);
     }
 
-    fn format_target_details<'a>(&self, target: &'a BuckTarget) -> Vec<Line<'a>> {
+    /// `configured` is set when a target platform is chosen, with the cquery details if known.
+    /// `deps` are the ones the cursor walks, from `BuckProject::displayed_deps`.
+    /// Also returns the line of the visibility or dep entry under the cursor.
+    fn format_target_details<'a>(
+        &self,
+        target: &'a BuckTarget,
+        configured: Option<Option<&'a ConfiguredTarget>>,
+        deps: &'a [String],
+    ) -> (Vec<Line<'a>>, Option<usize>) {
         let mut lines = vec![];
 
+        // The lists are cut short unless the cursor can walk them
+        let focused = self.current_pane == Pane::Details;
+        let cursor = self.attribute_tree_cursor(target);
+        let mut cursor_line = None;
+        let entry_style = |index: usize| {
+            if focused && index == cursor {
+                Style::default().bg(Color::Blue).fg(Color::White)
+            } else {
+                Style::default()
+            }
+        };
+
         // Basic Information Section
         lines.push(Line::from(vec![Span::styled(
             "▶ Target Information",
@@ -483,6 +707,28 @@ impl UI {
             ]));
         }
 
+        match configured {
+            Some(Some(ConfiguredTarget::Loaded { configuration, .. })) => {
+                lines.push(Line::from(vec![
+                    Span::styled("Configuration: ", Style::default().fg(Color::Cyan)),
+                    Span::styled(configuration, Style::default().fg(Color::Magenta)),
+                ]));
+            }
+            Some(Some(ConfiguredTarget::Failed(error))) => {
+                lines.push(Line::from(vec![
+                    Span::styled("Configuration: ", Style::default().fg(Color::Cyan)),
+                    Span::styled(error, Style::default().fg(Color::Red)),
+                ]));
+            }
+            Some(_) => {
+                lines.push(Line::from(vec![
+                    Span::styled("Configuration: ", Style::default().fg(Color::Cyan)),
+                    Span::styled("resolving...", Style::default().fg(Color::Gray)),
+                ]));
+            }
+            None => {}
+        }
+
         lines.push(Line::from(""));
         lines.push(Line::from(""));
 
@@ -497,9 +743,15 @@ impl UI {
             lines.push(Line::from(""));
 
             for (i, visibility) in target.visibility.iter().enumerate() {
-                if i < 5 {
+                if i < 5 || focused {
                     // Show first 5 visibility rules
-                    lines.push(Line::from(vec![Span::raw("  • "), Span::raw(visibility)]));
+                    if focused && i == cursor {
+                        cursor_line = Some(lines.len());
+                    }
+                    lines.push(Line::from(vec![
+                        Span::raw("  • "),
+                        Span::styled(visibility, entry_style(i)),
+                    ]));
                 } else if i == 5 {
                     lines.push(Line::from(vec![
                         Span::raw("  "),
@@ -516,24 +768,31 @@ impl UI {
         }
 
         // Dependencies Section
-        if !target.deps.is_empty() {
+        if !deps.is_empty() {
             lines.push(Line::from(vec![Span::styled(
-                format!("▶ Dependencies ({})", target.deps.len()),
+                format!("▶ Dependencies ({})", deps.len()),
                 Style::default()
                     .fg(Color::Green)
                     .add_modifier(Modifier::BOLD),
             )]));
             lines.push(Line::from(""));
 
-            for (i, dep) in target.deps.iter().enumerate() {
-                if i < 10 {
+            for (i, dep) in deps.iter().enumerate() {
+                if i < 10 || focused {
                     // Show first 10 dependencies
-                    lines.push(Line::from(vec![Span::raw("  • "), Span::raw(dep)]));
+                    let index = target.visibility.len() + i;
+                    if focused && index == cursor {
+                        cursor_line = Some(lines.len());
+                    }
+                    lines.push(Line::from(vec![
+                        Span::raw("  • "),
+                        Span::styled(dep, entry_style(index)),
+                    ]));
                 } else if i == 10 {
                     lines.push(Line::from(vec![
                         Span::raw("  "),
                         Span::styled(
-                            format!("... and {} more", target.deps.len() - 10),
+                            format!("... and {} more", deps.len() - 10),
                             Style::default().fg(Color::Gray),
                         ),
                     ]));
@@ -593,6 +852,171 @@ impl UI {
             ]));
         }
 
+        (lines, cursor_line)
+    }
+
+    fn details_title(tab: DetailsTab) -> Line<'static> {
+        let style = |t: DetailsTab| {
+            if t == tab {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Gray)
+            }
+        };
+        Line::from(vec![
+            Span::styled("Details", style(DetailsTab::Info)),
+            Span::raw(" | "),
+            Span::styled("Sources", style(DetailsTab::Sources)),
+        ])
+    }
+
+    /// Sources tab: the files of the selected target and a preview of the one under the cursor
+    fn draw_sources(&mut self, f: &mut Frame, area: Rect) {
+        let block_style = if self.current_pane == Pane::Details {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        };
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(40), // Files
+                Constraint::Percentage(60), // Preview
+            ])
+            .split(area);
+
+        let focused = self.current_pane == Pane::Details;
+        let items: Vec<ListItem> = self
+            .sources
+            .files
+            .iter()
+            .enumerate()
+            .map(|(i, file)| {
+                let (status, status_style) = match file.size {
+                    Some(size) => (format_size(size), Style::default().fg(Color::Gray)),
+                    None => ("missing".to_string(), Style::default().fg(Color::Red)),
+                };
+                let line = Line::from(vec![
+                    Span::raw(file.name.clone()),
+                    Span::styled(format!(" {}", status), status_style),
+                    Span::styled(
+                        format!(" ({})", file.attribute),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]);
+                let style = if i == self.sources.cursor && focused {
+                    Style::default().bg(Color::Blue).fg(Color::White)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(line).style(style)
+            })
+            .collect();
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(Self::details_title(DetailsTab::Sources))
+            .border_style(block_style);
+        if items.is_empty() {
+            let empty = Paragraph::new(Span::styled(
+                "No source files",
+                Style::default().fg(Color::Gray),
+            ))
+            .block(block);
+            f.render_widget(empty, chunks[0]);
+        } else {
+            self.sources_list_state.select(Some(self.sources.cursor));
+            let list = List::new(items).block(block);
+            f.render_stateful_widget(list, chunks[0], &mut self.sources_list_state);
+        }
+
+        let preview_title = self
+            .sources
+            .selected()
+            .map(|file| file.name.clone())
+            .unwrap_or_else(|| "Preview".to_string());
+        let preview_lines: Vec<Line> = self
+            .sources
+            .preview
+            .iter()
+            .map(|line| Line::from(line.as_str()))
+            .collect();
+        let preview = Paragraph::new(preview_lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(preview_title)
+                .border_style(Style::default().fg(Color::DarkGray)),
+        );
+        f.render_widget(preview, chunks[1]);
+    }
+
+    /// Cursor of the attribute tree, the tree state belongs to the previously shown target
+    /// until the next key press in the Details pane
+    fn attribute_tree_cursor(&self, target: &BuckTarget) -> usize {
+        if self.attribute_tree.target == target.full_target_label_name {
+            self.attribute_tree.cursor
+        } else {
+            0
+        }
+    }
+
+    /// "All attributes" section, a foldable tree of the raw `buck2 targets -A` attributes.
+    /// The cursor reaches the tree after the `label_count` dep and visibility entries.
+    fn format_attributes<'a>(&self, target: &'a BuckTarget, label_count: usize) -> Vec<Line<'a>> {
+        let mut lines = vec![
+            Line::from(""),
+            Line::from(vec![Span::styled(
+                format!("▶ All attributes ({})", target.attributes.len()),
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD),
+            )]),
+        ];
+
+        let same_target = self.attribute_tree.target == target.full_target_label_name;
+        let no_expanded = HashSet::new();
+        let expanded = if same_target {
+            &self.attribute_tree.expanded
+        } else {
+            &no_expanded
+        };
+        let cursor = self.attribute_tree_cursor(target);
+        let focused = self.current_pane == Pane::Details;
+
+        for (i, row) in attribute_rows(&target.attributes, expanded)
+            .into_iter()
+            .enumerate()
+        {
+            let marker = match (row.expandable, row.expanded) {
+                (true, true) => "▾ ",
+                (true, false) => "▸ ",
+                (false, _) => "  ",
+            };
+            let mut spans = vec![Span::raw(format!("{}{}", "  ".repeat(row.depth), marker))];
+            match row.key {
+                Some(key) => spans.push(Span::styled(
+                    format!("{}: ", key),
+                    Style::default().fg(Color::Cyan),
+                )),
+                None => spans.push(Span::raw("• ")),
+            }
+            spans.push(Span::styled(
+                row.value,
+                if row.expandable {
+                    Style::default().fg(Color::Gray)
+                } else {
+                    Style::default()
+                },
+            ));
+
+            let mut line = Line::from(spans);
+            if focused && label_count + i == cursor {
+                line = line.style(Style::default().bg(Color::Blue).fg(Color::White));
+            }
+            lines.push(line);
+        }
+
         lines
     }
 
@@ -739,12 +1163,21 @@ impl UI {
             .split(popup_layout[1])[1]
     }
 
-    fn draw_path_bar(&self, f: &mut Frame, area: Rect, project: &BuckProject) {
+    /// ` [cell]` suffix for directories at the root of a cell
+    fn cell_marker(project: &BuckProject, dir: &Path) -> Option<Span<'static>> {
+        project
+            .cell_rooted_at(dir)
+            .map(|cell| Span::styled(format!(" [{}]", cell), Style::default().fg(Color::Cyan)))
+    }
+
+    fn draw_path_bar(&self, f: &mut Frame, area: Rect, project: &BuckProject, tabs: &Tabs) {
         // Convert path to a more readable format, similar to yazi
         let current_path = &project.current_path;
 
-        // Try to make path relative to home directory for cleaner display
-        let display_path = if let Some(home) = dirs::home_dir() {
+        // Show the package label inside a cell, otherwise a path relative to the home directory
+        let display_path = if let Some(package) = project.package_for_path(current_path) {
+            package
+        } else if let Some(home) = dirs::home_dir() {
             if let Ok(relative) = current_path.strip_prefix(&home) {
                 format!("~/{}", relative.display())
             } else {
@@ -754,17 +1187,91 @@ impl UI {
             current_path.display().to_string()
         };
 
-        let path_text = vec![Line::from(vec![Span::styled(
+        let mut spans = vec![Span::styled(
             display_path,
             Style::default().fg(Color::Yellow),
-        )])];
+        )];
+        if let Some(platform) = &project.target_platform {
+            spans.push(Span::styled(
+                format!("  [{}]", platform),
+                Style::default().fg(Color::Magenta),
+            ));
+        }
+        let path_text = vec![Line::from(spans)];
 
         let path_bar = Paragraph::new(path_text);
 
+        // Tab strip on the right, only once there is more than one tab
+        if tabs.count() > 1 {
+            let mut tab_spans = Vec::new();
+            for (i, title) in tabs.titles(current_path).into_iter().enumerate() {
+                let style = if i == tabs.active {
+                    Style::default().bg(Color::Blue).fg(Color::White)
+                } else {
+                    Style::default().fg(Color::Gray)
+                };
+                tab_spans.push(Span::raw(" "));
+                tab_spans.push(Span::styled(format!(" {} {} ", i + 1, title), style));
+            }
+            let strip = Line::from(tab_spans);
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Min(0), Constraint::Length(strip.width() as u16)])
+                .split(area);
+            f.render_widget(path_bar, chunks[0]);
+            f.render_widget(Paragraph::new(strip), chunks[1]);
+            return;
+        }
+
         f.render_widget(path_bar, area);
     }
 
-    pub fn draw_actions_popup(&mut self, f: &mut Frame, selected_action: usize) {
+    fn draw_status_bar(&self, f: &mut Frame, area: Rect, project: &BuckProject) {
+        let line = match &self.status_message {
+            Some(StatusMessage::Info(message)) => Line::from(Span::styled(
+                message.as_str(),
+                Style::default().fg(Color::Green),
+            )),
+            Some(StatusMessage::Error(message)) => Line::from(Span::styled(
+                message.as_str(),
+                Style::default().fg(Color::Red),
+            )),
+            None => Line::from(""),
+        };
+
+        // Count of the targets batch actions will run on, on the right
+        let selected = project.selected_labels().len();
+        if selected > 0 {
+            let count = Line::from(Span::styled(
+                format!(" {} selected (Esc: clear) ", selected),
+                Style::default().fg(Color::Black).bg(Color::Green),
+            ));
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Min(0), Constraint::Length(count.width() as u16)])
+                .split(area);
+            f.render_widget(Paragraph::new(line), chunks[0]);
+            f.render_widget(Paragraph::new(count), chunks[1]);
+            return;
+        }
+
+        f.render_widget(Paragraph::new(line), area);
+    }
+
+    pub fn show_info(&mut self, message: impl Into<String>) {
+        self.status_message = Some(StatusMessage::Info(message.into()));
+    }
+
+    pub fn show_error(&mut self, message: impl Into<String>) {
+        self.status_message = Some(StatusMessage::Error(message.into()));
+    }
+
+    pub fn draw_actions_popup(
+        &mut self,
+        f: &mut Frame,
+        selected_action: usize,
+        target_count: usize,
+    ) {
         let popup_area = self.centered_rect(30, 40, f.area());
         f.render_widget(Clear, popup_area);
 
@@ -790,11 +1297,1111 @@ impl UI {
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title("Actions")
+                    .title(if target_count > 1 {
+                        format!("Actions ({} targets)", target_count)
+                    } else {
+                        "Actions".to_string()
+                    })
                     .border_style(Style::default().fg(Color::Yellow)),
             )
             .highlight_style(Style::default().add_modifier(Modifier::BOLD));
 
         f.render_stateful_widget(actions_list, popup_area, &mut self.actions_list_state);
     }
+
+    pub fn draw_goto_prompt(&self, f: &mut Frame, prompt: &GotoPrompt) {
+        let popup_area = self.centered_rect(60, 20, f.area());
+        f.render_widget(Clear, popup_area);
+
+        let hint = match &prompt.error {
+            Some(error) => Span::styled(error.as_str(), Style::default().fg(Color::Red)),
+            None => Span::styled(
+                "cell//path:name, //path:name, :name or a directory, ↑↓: history",
+                Style::default().fg(Color::Gray),
+            ),
+        };
+        let lines = vec![
+            Line::from(vec![
+                Span::styled("> ", Style::default().fg(Color::Cyan)),
+                Span::raw(&prompt.input),
+            ]),
+            Line::from(""),
+            Line::from(hint),
+        ];
+
+        let paragraph = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Go to label (Enter: go, Esc: cancel)")
+                    .border_style(Style::default().fg(Color::Yellow)),
+            )
+            .wrap(Wrap { trim: false });
+        f.render_widget(paragraph, popup_area);
+    }
+
+    pub fn draw_recent(&mut self, f: &mut Frame, view: &RecentView, recent: &RecentTargets) {
+        let popup_area = self.centered_rect(70, 60, f.area());
+        f.render_widget(Clear, popup_area);
+
+        let items: Vec<ListItem> = recent
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let (result, result_style) = match &entry.result {
+                    ActionResult::Done => (String::new(), Style::default()),
+                    ActionResult::Running => {
+                        (" running".to_string(), Style::default().fg(Color::Yellow))
+                    }
+                    ActionResult::Interrupted => {
+                        (" interrupted".to_string(), Style::default().fg(Color::Gray))
+                    }
+                    ActionResult::Succeeded => {
+                        (" ✓".to_string(), Style::default().fg(Color::Green))
+                    }
+                    ActionResult::Failed(reason) => {
+                        (format!(" ✗ {}", reason), Style::default().fg(Color::Red))
+                    }
+                };
+                let line = Line::from(vec![
+                    Span::raw(entry.label.as_str()),
+                    Span::styled(
+                        format!("  {} {}", entry.action.name(), format_age(entry.time)),
+                        Style::default().fg(Color::Gray),
+                    ),
+                    Span::styled(result, result_style),
+                ]);
+                let style = if i == view.selected {
+                    Style::default().bg(Color::Blue).fg(Color::White)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(line).style(style)
+            })
+            .collect();
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Recent targets (Enter: go, Esc: close)")
+            .border_style(Style::default().fg(Color::Yellow));
+        if items.is_empty() {
+            let empty = Paragraph::new(Span::styled(
+                "No recent targets yet",
+                Style::default().fg(Color::Gray),
+            ))
+            .block(block);
+            f.render_widget(empty, popup_area);
+            return;
+        }
+
+        self.recent_list_state.select(Some(view.selected));
+        let list = List::new(items).block(block);
+        f.render_stateful_widget(list, popup_area, &mut self.recent_list_state);
+    }
+
+    pub fn draw_bookmarks(
+        &mut self,
+        f: &mut Frame,
+        view: &BookmarksView,
+        bookmarks: &Bookmarks,
+        project: &BuckProject,
+    ) {
+        let popup_area = self.centered_rect(60, 50, f.area());
+        f.render_widget(Clear, popup_area);
+
+        let items: Vec<ListItem> = bookmarks
+            .marks
+            .iter()
+            .enumerate()
+            .map(|(i, bookmark)| {
+                let location = &bookmark.location;
+                let place = location.target.clone().unwrap_or_else(|| {
+                    project
+                        .package_for_path(&location.selected_directory)
+                        .unwrap_or_else(|| location.selected_directory.display().to_string())
+                });
+                let kind = if location.target.is_some() {
+                    " target"
+                } else {
+                    " directory"
+                };
+
+                let line = Line::from(vec![
+                    Span::styled(
+                        format!("{} ", bookmark.mark),
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::raw(place),
+                    Span::styled(kind, Style::default().fg(Color::Gray)),
+                ]);
+                let style = if i == view.selected {
+                    Style::default().bg(Color::Blue).fg(Color::White)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(line).style(style)
+            })
+            .collect();
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Bookmarks (mark or Enter: go, d: delete, Esc: close)")
+            .border_style(Style::default().fg(Color::Yellow));
+        if items.is_empty() {
+            let empty = Paragraph::new(Span::styled(
+                "No bookmarks, press m and a key to set one",
+                Style::default().fg(Color::Gray),
+            ))
+            .block(block);
+            f.render_widget(empty, popup_area);
+            return;
+        }
+
+        self.bookmarks_list_state.select(Some(view.selected));
+        let list = List::new(items).block(block);
+        f.render_stateful_widget(list, popup_area, &mut self.bookmarks_list_state);
+    }
+
+    pub fn draw_jump_list(&mut self, f: &mut Frame, view: &JumpListView, project: &BuckProject) {
+        let popup_area = self.centered_rect(60, 50, f.area());
+        f.render_widget(Clear, popup_area);
+
+        let jump_list = &project.jump_list;
+        let count = jump_list.entries.len();
+        let items: Vec<ListItem> = jump_list
+            .entries
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(i, location)| {
+                let place = project
+                    .package_for_path(&location.selected_directory)
+                    .unwrap_or_else(|| location.selected_directory.display().to_string());
+                let mut spans = vec![Span::styled(place, Style::default().fg(Color::Cyan))];
+                if let Some(name) = location.target.as_deref().and_then(|l| l.rsplit_once(':')) {
+                    spans.push(Span::raw(format!(":{}", name.1)));
+                }
+                if i == jump_list.position {
+                    spans.push(Span::styled(" (here)", Style::default().fg(Color::Green)));
+                }
+
+                let style = if i == view.selected {
+                    Style::default().bg(Color::Blue).fg(Color::White)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(Line::from(spans)).style(style)
+            })
+            .collect();
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(
+                "Jump list ({}) j/k: select, Enter: go, Esc: close",
+                count
+            ))
+            .border_style(Style::default().fg(Color::Yellow));
+        if items.is_empty() {
+            let empty = Paragraph::new(Span::styled(
+                "No jumps yet",
+                Style::default().fg(Color::Gray),
+            ))
+            .block(block);
+            f.render_widget(empty, popup_area);
+            return;
+        }
+
+        // Drawn newest first
+        self.jump_list_state
+            .select(Some(count - 1 - view.selected.min(count - 1)));
+        let list = List::new(items).block(block);
+        f.render_stateful_widget(list, popup_area, &mut self.jump_list_state);
+    }
+
+    pub fn draw_query_console(&mut self, f: &mut Frame, console: &QueryConsole) {
+        let popup_area = self.centered_rect(80, 70, f.area());
+        f.render_widget(Clear, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Query input
+                Constraint::Min(0),    // Results
+            ])
+            .split(popup_area);
+
+        let input_style = match console.focus {
+            ConsoleFocus::Input => Style::default().fg(Color::Yellow),
+            ConsoleFocus::Results => Style::default(),
+        };
+
+        let input = Paragraph::new(Line::from(vec![
+            Span::styled("> ", Style::default().fg(Color::Cyan)),
+            Span::raw(&console.input),
+        ]))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(
+                    "buck2 {} (Ctrl+T: uquery/cquery, ↑↓: history, Tab: results)",
+                    console.kind.command()
+                ))
+                .border_style(input_style),
+        );
+        f.render_widget(input, chunks[0]);
+
+        self.draw_query_results(
+            f,
+            chunks[1],
+            &console.results,
+            format!("Results ({})", console.results.labels.len()),
+            console.focus == ConsoleFocus::Results,
+        );
+    }
+
+    pub fn draw_owner_lookup(&mut self, f: &mut Frame, lookup: &OwnerLookup) {
+        let popup_area = self.centered_rect(70, 60, f.area());
+        f.render_widget(Clear, popup_area);
+
+        let suggestion_rows = if lookup.focus == ConsoleFocus::Input {
+            lookup.suggestions.len() as u16
+        } else {
+            0
+        };
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3 + suggestion_rows), // File input and completions
+                Constraint::Min(0),                      // Owners
+            ])
+            .split(popup_area);
+
+        let input_style = match lookup.focus {
+            ConsoleFocus::Input => Style::default().fg(Color::Yellow),
+            ConsoleFocus::Results => Style::default(),
+        };
+
+        let mut lines = vec![Line::from(vec![
+            Span::styled("File: ", Style::default().fg(Color::Cyan)),
+            Span::raw(&lookup.input),
+        ])];
+        if lookup.focus == ConsoleFocus::Input {
+            for (i, suggestion) in lookup.suggestions.iter().enumerate() {
+                let style = if i == lookup.suggestion_selected {
+                    Style::default().bg(Color::Blue).fg(Color::White)
+                } else {
+                    Style::default().fg(Color::Gray)
+                };
+                lines.push(Line::from(Span::styled(format!("  {}", suggestion), style)));
+            }
+        }
+
+        let input = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Owner lookup (Tab: complete / results, Enter: find owners)")
+                .border_style(input_style),
+        );
+        f.render_widget(input, chunks[0]);
+
+        let title = if lookup.file.is_empty() {
+            "Owners".to_string()
+        } else {
+            format!(
+                "Owners of {} ({})",
+                lookup.file,
+                lookup.results.labels.len()
+            )
+        };
+        self.draw_query_results(
+            f,
+            chunks[1],
+            &lookup.results,
+            title,
+            lookup.focus == ConsoleFocus::Results,
+        );
+    }
+
+    pub fn draw_changed_targets(
+        &mut self,
+        f: &mut Frame,
+        view: &ChangedTargetsView,
+        recent: &RecentTargets,
+    ) {
+        let popup_area = self.centered_rect(70, 70, f.area());
+        f.render_widget(Clear, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(5), // Changed files and query parameters
+                Constraint::Min(0),    // Affected targets
+            ])
+            .split(popup_area);
+
+        let files = if view.loading_status {
+            Span::styled("reading status...", Style::default().fg(Color::Yellow))
+        } else if let Some(error) = &view.status_error {
+            Span::styled(error.as_str(), Style::default().fg(Color::Red))
+        } else {
+            Span::raw(format!(
+                "{} changed in {}",
+                view.files.len(),
+                view.vcs.map(|vcs| vcs.name()).unwrap_or_default()
+            ))
+        };
+
+        let mode = if view.include_rdeps {
+            "owners and their rdeps"
+        } else {
+            "owners"
+        };
+        let universe = if view.editing_universe {
+            Line::from(vec![
+                Span::styled("Universe: ", Style::default().fg(Color::Cyan)),
+                Span::styled(&view.universe_input, Style::default().fg(Color::Yellow)),
+                Span::styled(
+                    "  (Enter: apply, Esc: cancel)",
+                    Style::default().fg(Color::Gray),
+                ),
+            ])
+        } else {
+            Line::from(vec![
+                Span::styled("Universe: ", Style::default().fg(Color::Cyan)),
+                Span::raw(&view.universe),
+                Span::styled(" (u: change)", Style::default().fg(Color::Gray)),
+            ])
+        };
+
+        let header = Paragraph::new(vec![
+            Line::from(vec![
+                Span::styled("Files: ", Style::default().fg(Color::Cyan)),
+                files,
+            ]),
+            Line::from(vec![
+                Span::styled("Showing: ", Style::default().fg(Color::Cyan)),
+                Span::raw(mode),
+                Span::styled(" (r: toggle rdeps)", Style::default().fg(Color::Gray)),
+            ]),
+            universe,
+        ])
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Changed targets (R: refresh)"),
+        );
+        f.render_widget(header, chunks[0]);
+
+        let action = match view.running_action(recent) {
+            Some(action) => format!(
+                ", running buck2 {}...",
+                action.command().unwrap_or_default()
+            ),
+            None => ", b: build all, t: test all".to_string(),
+        };
+        let title = format!("Affected targets ({}{})", view.results.labels.len(), action);
+        self.draw_query_results(f, chunks[1], &view.results, title, !view.editing_universe);
+    }
+
+    pub fn draw_rdeps_view(&mut self, f: &mut Frame, view: &RdepsView) {
+        let popup_area = self.centered_rect(70, 70, f.area());
+        f.render_widget(Clear, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(5), // Query parameters
+                Constraint::Min(0),    // Dependents
+            ])
+            .split(popup_area);
+
+        let universe = if view.editing_universe {
+            Line::from(vec![
+                Span::styled("Universe: ", Style::default().fg(Color::Cyan)),
+                Span::styled(&view.universe_input, Style::default().fg(Color::Yellow)),
+                Span::styled(
+                    "  (Enter: apply, Esc: cancel)",
+                    Style::default().fg(Color::Gray),
+                ),
+            ])
+        } else {
+            Line::from(vec![
+                Span::styled("Universe: ", Style::default().fg(Color::Cyan)),
+                Span::raw(&view.universe_pattern),
+                Span::styled(
+                    format!(" ({}, u: change)", view.universe.description()),
+                    Style::default().fg(Color::Gray),
+                ),
+            ])
+        };
+
+        let header = Paragraph::new(vec![
+            Line::from(vec![
+                Span::styled("Target: ", Style::default().fg(Color::Cyan)),
+                Span::raw(&view.target),
+            ]),
+            universe,
+            Line::from(vec![
+                Span::styled("Depth: ", Style::default().fg(Color::Cyan)),
+                Span::raw(view.depth.to_string()),
+                Span::styled(" (+/-: widen/narrow)", Style::default().fg(Color::Gray)),
+            ]),
+        ])
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Reverse dependencies")
+                .border_style(Style::default().fg(Color::Yellow)),
+        );
+        f.render_widget(header, chunks[0]);
+
+        self.draw_query_results(
+            f,
+            chunks[1],
+            &view.results,
+            format!("Dependents ({})", view.results.labels.len()),
+            !view.editing_universe,
+        );
+    }
+
+    pub fn draw_dep_tree(&mut self, f: &mut Frame, tree: &DepTree) {
+        let popup_area = self.centered_rect(80, 80, f.area());
+        f.render_widget(Clear, popup_area);
+
+        let visible = tree.visible_nodes();
+        let items: Vec<ListItem> = visible
+            .iter()
+            .enumerate()
+            .map(|(row, &index)| {
+                let node = &tree.nodes[index];
+                let state = tree.deps.get(&node.label);
+
+                let expander = match (node.mark, node.expanded) {
+                    (NodeMark::Cycle, _) => "↻ ",
+                    (_, true) => "▾ ",
+                    (_, false) => match state {
+                        Some(DepsState::Loaded(deps)) if deps.is_empty() => "  ",
+                        _ => "▸ ",
+                    },
+                };
+
+                let mut spans = vec![
+                    Span::raw("  ".repeat(node.depth)),
+                    Span::styled(expander, Style::default().fg(Color::Cyan)),
+                    Span::raw(node.label.as_str()),
+                ];
+
+                if let Some((count, complete)) = tree.transitive_counts.get(&node.label) {
+                    let count = if *complete {
+                        format!(" [{}]", count)
+                    } else {
+                        format!(" [{}+]", count)
+                    };
+                    spans.push(Span::styled(count, Style::default().fg(Color::Green)));
+                }
+
+                match node.mark {
+                    NodeMark::Cycle => {
+                        spans.push(Span::styled(" (cycle)", Style::default().fg(Color::Red)))
+                    }
+                    NodeMark::Seen => {
+                        spans.push(Span::styled(" (seen)", Style::default().fg(Color::Gray)))
+                    }
+                    NodeMark::None => {}
+                }
+
+                if node.expanded {
+                    match state {
+                        Some(DepsState::Loading) => spans.push(Span::styled(
+                            " loading...",
+                            Style::default().fg(Color::Yellow),
+                        )),
+                        Some(DepsState::Failed(error)) => spans.push(Span::styled(
+                            format!(" failed: {}", error.lines().last().unwrap_or_default()),
+                            Style::default().fg(Color::Red),
+                        )),
+                        _ => {}
+                    }
+                }
+
+                let style = if row == tree.selected {
+                    Style::default().bg(Color::Blue).fg(Color::White)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(Line::from(spans)).style(style)
+            })
+            .collect();
+
+        self.dep_tree_list_state.select(Some(tree.selected));
+
+        let title = format!(
+            "Dependency tree: {} (l: expand, h: collapse, e: expand 3 levels, Enter: jump)",
+            tree.nodes
+                .first()
+                .map(|n| n.label.as_str())
+                .unwrap_or_default()
+        );
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(title)
+                    .border_style(Style::default().fg(Color::Yellow)),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD));
+
+        f.render_stateful_widget(list, popup_area, &mut self.dep_tree_list_state);
+    }
+
+    pub fn draw_dep_path_view(&mut self, f: &mut Frame, view: &DepPathView) {
+        let popup_area = self.centered_rect(80, 80, f.area());
+        f.render_widget(Clear, popup_area);
+
+        let show_suggestions = view.focus == DepPathFocus::Input && !view.suggestions.is_empty();
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Label input
+                Constraint::Length(if show_suggestions {
+                    view.suggestions.len() as u16 + 2
+                } else {
+                    0
+                }), // Suggestions from loaded targets
+                Constraint::Min(0),    // Paths
+            ])
+            .split(popup_area);
+
+        let (input_style, paths_style) = match view.focus {
+            DepPathFocus::Input => (Style::default().fg(Color::Yellow), Style::default()),
+            DepPathFocus::Paths => (Style::default(), Style::default().fg(Color::Yellow)),
+        };
+
+        let input = Paragraph::new(Line::from(vec![
+            Span::styled("> ", Style::default().fg(Color::Cyan)),
+            Span::raw(&view.input),
+        ]))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(
+                    "Why does {} depend on ...? ({}, Ctrl+T: somepath/allpaths)",
+                    view.from,
+                    view.kind.function()
+                ))
+                .border_style(input_style),
+        );
+        f.render_widget(input, chunks[0]);
+
+        if show_suggestions {
+            let items: Vec<ListItem> = view
+                .suggestions
+                .iter()
+                .enumerate()
+                .map(|(i, label)| {
+                    let style = if i == view.suggestion_selected {
+                        Style::default().bg(Color::Blue).fg(Color::White)
+                    } else {
+                        Style::default()
+                    };
+                    ListItem::new(label.as_str()).style(style)
+                })
+                .collect();
+            let suggestions = List::new(items).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Loaded targets (Tab: complete)"),
+            );
+            f.render_widget(suggestions, chunks[1]);
+        }
+
+        let paths_block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(
+                "Paths to {} ({}) (l: fold, Enter: jump)",
+                view.to,
+                view.paths.len()
+            ))
+            .border_style(paths_style);
+
+        if view.running {
+            let running = Paragraph::new("Running query...")
+                .style(Style::default().fg(Color::Yellow))
+                .block(paths_block);
+            f.render_widget(running, chunks[2]);
+            return;
+        }
+        if let Some(error) = &view.error {
+            let error = Paragraph::new(error.as_str())
+                .style(Style::default().fg(Color::Red))
+                .block(paths_block)
+                .wrap(Wrap { trim: false });
+            f.render_widget(error, chunks[2]);
+            return;
+        }
+
+        let items: Vec<ListItem> = view
+            .rows()
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let line = match *row {
+                    PathRow::Header(path_index) => {
+                        let path = &view.paths[path_index];
+                        let expander = if path.expanded { "▾ " } else { "▸ " };
+                        Line::from(vec![
+                            Span::styled(expander, Style::default().fg(Color::Cyan)),
+                            Span::styled(
+                                format!(
+                                    "Path {}: {} hops",
+                                    path_index + 1,
+                                    path.hops.len().saturating_sub(1)
+                                ),
+                                Style::default().fg(Color::Green),
+                            ),
+                        ])
+                    }
+                    PathRow::Hop(path, hop) => {
+                        let hop_data = &view.paths[path].hops[hop];
+                        let mut spans = vec![Span::raw("    ")];
+                        if hop > 0 {
+                            let via = if hop_data.via.is_empty() {
+                                "?".to_string()
+                            } else {
+                                hop_data.via.join(", ")
+                            };
+                            spans.push(Span::raw("└ "));
+                            spans.push(Span::styled(via, Style::default().fg(Color::Cyan)));
+                            spans.push(Span::raw(" → "));
+                        }
+                        spans.push(Span::raw(hop_data.label.as_str()));
+                        Line::from(spans)
+                    }
+                };
+
+                let style = if i == view.selected && view.focus == DepPathFocus::Paths {
+                    Style::default().bg(Color::Blue).fg(Color::White)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(line).style(style)
+            })
+            .collect();
+
+        self.dep_path_list_state.select(Some(view.selected));
+
+        let list = List::new(items)
+            .block(paths_block)
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD));
+        f.render_stateful_widget(list, chunks[2], &mut self.dep_path_list_state);
+    }
+
+    /// Render query results as a selectable list, or the running/error state
+    fn draw_query_results(
+        &mut self,
+        f: &mut Frame,
+        area: Rect,
+        results: &QueryResults,
+        title: String,
+        focused: bool,
+    ) {
+        let border_style = if focused {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(border_style);
+
+        if results.running {
+            let running = Paragraph::new("Running query...")
+                .style(Style::default().fg(Color::Yellow))
+                .block(block);
+            f.render_widget(running, area);
+        } else if let Some(error) = &results.error {
+            let error = Paragraph::new(error.as_str())
+                .style(Style::default().fg(Color::Red))
+                .block(block)
+                .wrap(Wrap { trim: false });
+            f.render_widget(error, area);
+        } else {
+            let items: Vec<ListItem> = results
+                .labels
+                .iter()
+                .enumerate()
+                .map(|(i, label)| {
+                    let style = if i == results.selected && focused {
+                        Style::default().bg(Color::Blue).fg(Color::White)
+                    } else {
+                        Style::default()
+                    };
+                    ListItem::new(label.as_str()).style(style)
+                })
+                .collect();
+
+            self.query_results_list_state.select(Some(results.selected));
+
+            let list = List::new(items)
+                .block(block)
+                .highlight_style(Style::default().add_modifier(Modifier::BOLD));
+            f.render_stateful_widget(list, area, &mut self.query_results_list_state);
+        }
+    }
+
+    pub fn draw_export_dialog(&mut self, f: &mut Frame, dialog: &ExportDialog) {
+        let popup_area = self.centered_rect(60, 40, f.area());
+        f.render_widget(Clear, popup_area);
+
+        let roots = dialog
+            .roots()
+            .unwrap_or_else(|| "(nothing selected)".to_string());
+        let scope = match dialog.scope {
+            ExportScope::Target => "selected target",
+            ExportScope::Package => "whole package",
+        };
+        let depth = if dialog.depth == 0 {
+            "unlimited".to_string()
+        } else {
+            dialog.depth.to_string()
+        };
+
+        let field_line = |field: ExportField, name: &'static str, value: String| {
+            let selected = dialog.field == field;
+            let name_style = if selected {
+                Style::default().bg(Color::Blue).fg(Color::White)
+            } else {
+                Style::default().fg(Color::Cyan)
+            };
+            Line::from(vec![
+                Span::styled(format!("{:<8}", name), name_style),
+                Span::raw(" "),
+                Span::styled(
+                    value,
+                    if selected && field == ExportField::Path {
+                        Style::default().fg(Color::Yellow)
+                    } else {
+                        Style::default()
+                    },
+                ),
+            ])
+        };
+
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled("Roots: ", Style::default().fg(Color::Gray)),
+                Span::raw(roots),
+            ]),
+            Line::from(""),
+            field_line(ExportField::Scope, "Scope", scope.to_string()),
+            field_line(
+                ExportField::Format,
+                "Format",
+                dialog.format.name().to_string(),
+            ),
+            field_line(ExportField::Depth, "Depth", depth),
+            field_line(ExportField::Path, "Output", dialog.path.clone()),
+            Line::from(""),
+        ];
+
+        if dialog.running {
+            lines.push(Line::from(Span::styled(
+                "Exporting...",
+                Style::default().fg(Color::Yellow),
+            )));
+        } else {
+            lines.push(Line::from(Span::styled(
+                "j/k: field, h/l: change, Enter: export, Esc: close",
+                Style::default().fg(Color::Gray),
+            )));
+        }
+
+        let paragraph = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Export dependency graph")
+                    .border_style(Style::default().fg(Color::Yellow)),
+            )
+            .wrap(Wrap { trim: false });
+        f.render_widget(paragraph, popup_area);
+    }
+
+    pub fn draw_graph_view(&mut self, f: &mut Frame, view: &GraphView) {
+        let popup_area = self.centered_rect(90, 85, f.area());
+        f.render_widget(Clear, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),    // Canvas
+                Constraint::Length(3), // Selected node
+            ])
+            .split(popup_area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(
+                "Graph of {} ({} level{})",
+                view.focus,
+                view.levels,
+                if view.levels == 1 { "" } else { "s" }
+            ))
+            .border_style(Style::default().fg(Color::Yellow));
+
+        if view.nodes.is_empty() {
+            let (text, color) = match &view.error {
+                Some(error) => (error.clone(), Color::Red),
+                None if view.running => ("Loading graph...".to_string(), Color::Yellow),
+                None => ("No dependencies".to_string(), Color::Gray),
+            };
+            let paragraph = Paragraph::new(text)
+                .style(Style::default().fg(color))
+                .block(block)
+                .wrap(Wrap { trim: false });
+            f.render_widget(paragraph, chunks[0]);
+        } else {
+            // One canvas unit per terminal cell, centered on the pan position
+            let inner = block.inner(chunks[0]);
+            let half_width = inner.width.saturating_sub(1) as f64 / 2.0;
+            let half_height = inner.height.saturating_sub(1) as f64 / 2.0;
+            let (pan_x, pan_y) = view.pan;
+
+            let canvas = Canvas::default()
+                .block(block)
+                .marker(Marker::Braille)
+                .x_bounds([pan_x - half_width, pan_x + half_width])
+                .y_bounds([pan_y - half_height, pan_y + half_height])
+                .paint(|ctx| {
+                    for &(from, to) in &view.edges {
+                        let (a, b) = (&view.nodes[from], &view.nodes[to]);
+                        let touches_selection = from == view.selected || to == view.selected;
+                        // Dependencies are drawn below their dependents
+                        let (y1, y2) = if b.y < a.y {
+                            (a.y - 1.0, b.y + 1.0)
+                        } else if b.y > a.y {
+                            (a.y + 1.0, b.y - 1.0)
+                        } else {
+                            (a.y, b.y)
+                        };
+                        ctx.draw(&CanvasLine {
+                            x1: a.x,
+                            y1,
+                            x2: b.x,
+                            y2,
+                            color: if touches_selection {
+                                Color::Yellow
+                            } else {
+                                Color::DarkGray
+                            },
+                        });
+                    }
+                    ctx.layer();
+
+                    for (i, node) in view.nodes.iter().enumerate() {
+                        let color = if i == view.selected {
+                            Color::Yellow
+                        } else if node.label == view.focus {
+                            Color::Cyan
+                        } else if node.layer < 0 {
+                            Color::Magenta
+                        } else {
+                            Color::Green
+                        };
+                        ctx.draw(&Rectangle {
+                            x: node.x - node.width / 2.0,
+                            y: node.y - 1.0,
+                            width: node.width,
+                            height: 2.0,
+                            color,
+                        });
+                    }
+                    ctx.layer();
+
+                    for (i, node) in view.nodes.iter().enumerate() {
+                        let style = if i == view.selected {
+                            Style::default()
+                                .fg(Color::Yellow)
+                                .add_modifier(Modifier::BOLD)
+                        } else if node.label == view.focus {
+                            Style::default()
+                                .fg(Color::Cyan)
+                                .add_modifier(Modifier::BOLD)
+                        } else {
+                            Style::default()
+                        };
+                        ctx.print(
+                            node.x - node.width / 2.0 + 1.0,
+                            node.y,
+                            Span::styled(node.text.clone(), style),
+                        );
+                    }
+                });
+            f.render_widget(canvas, chunks[0]);
+        }
+
+        let selected = match view.selected_node() {
+            Some(node) => Line::from(vec![
+                Span::styled(node.label.as_str(), Style::default().fg(Color::Cyan)),
+                Span::styled(
+                    format!(" ({})", node.rule_type),
+                    Style::default().fg(Color::Gray),
+                ),
+            ]),
+            None => Line::from(""),
+        };
+        let help = Paragraph::new(vec![
+            selected,
+            Line::from(Span::styled(
+                "hjkl: select, arrows: pan, +/-: levels, Enter: refocus, Backspace: back, o: open, c: center",
+                Style::default().fg(Color::Gray),
+            )),
+        ])
+        .block(Block::default().borders(Borders::LEFT | Borders::RIGHT | Borders::BOTTOM));
+        f.render_widget(help, chunks[1]);
+    }
+
+    pub fn draw_cell_browser(&mut self, f: &mut Frame, browser: &CellBrowser) {
+        let popup_area = self.centered_rect(60, 50, f.area());
+        f.render_widget(Clear, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Filter
+                Constraint::Min(0),    // Cells
+            ])
+            .split(popup_area);
+
+        let input = Paragraph::new(Line::from(vec![
+            Span::styled("> ", Style::default().fg(Color::Cyan)),
+            Span::styled(&browser.input, Style::default().fg(Color::Yellow)),
+        ]))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Cells ({})", browser.cells.len()))
+                .border_style(Style::default().fg(Color::Yellow)),
+        );
+        f.render_widget(input, chunks[0]);
+
+        let items: Vec<ListItem> = browser
+            .visible
+            .iter()
+            .enumerate()
+            .map(|(i, &index)| {
+                let (name, path) = &browser.cells[index];
+                let path = match path.strip_prefix(&browser.repo_root) {
+                    Ok(relative) if relative.as_os_str().is_empty() => ".".to_string(),
+                    Ok(relative) => relative.display().to_string(),
+                    Err(_) => path.display().to_string(),
+                };
+
+                let mut spans = vec![
+                    Span::styled(format!("{}//", name), Style::default().fg(Color::Cyan)),
+                    Span::styled(format!("  {}", path), Style::default().fg(Color::Gray)),
+                ];
+                if browser.current_cell.as_ref() == Some(name) {
+                    spans.push(Span::styled(
+                        " (current)",
+                        Style::default().fg(Color::Green),
+                    ));
+                }
+
+                let style = if i == browser.selected {
+                    Style::default().bg(Color::Blue).fg(Color::White)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(Line::from(spans)).style(style)
+            })
+            .collect();
+
+        self.cell_list_state.select(Some(browser.selected));
+        let list = List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("↑/↓: select, Enter: go to cell root, Esc: cancel"),
+        );
+        f.render_stateful_widget(list, chunks[1], &mut self.cell_list_state);
+    }
+
+    pub fn draw_platform_picker(&mut self, f: &mut Frame, picker: &PlatformPicker) {
+        let popup_area = self.centered_rect(50, 50, f.area());
+        f.render_widget(Clear, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Filter
+                Constraint::Min(0),    // Platforms
+            ])
+            .split(popup_area);
+
+        let input = Paragraph::new(Line::from(vec![
+            Span::styled("> ", Style::default().fg(Color::Cyan)),
+            Span::styled(&picker.input, Style::default().fg(Color::Yellow)),
+        ]))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Target platform")
+                .border_style(Style::default().fg(Color::Yellow)),
+        );
+        f.render_widget(input, chunks[0]);
+
+        let mut items: Vec<ListItem> = picker
+            .choices
+            .iter()
+            .enumerate()
+            .map(|(i, choice)| {
+                let mut spans = match choice {
+                    PlatformChoice::Unconfigured => vec![Span::styled(
+                        "Unconfigured (uquery)",
+                        Style::default().fg(Color::Gray),
+                    )],
+                    PlatformChoice::Platform(platform) => vec![Span::raw(platform.as_str())],
+                };
+                if let PlatformChoice::Platform(platform) = choice
+                    && picker.default_platform.as_ref() == Some(platform)
+                {
+                    spans.push(Span::styled(
+                        " (default)",
+                        Style::default().fg(Color::Green),
+                    ));
+                }
+
+                let style = if i == picker.selected {
+                    Style::default().bg(Color::Blue).fg(Color::White)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(Line::from(spans)).style(style)
+            })
+            .collect();
+
+        if picker.discovered.running {
+            items.push(ListItem::new(Span::styled(
+                "Looking for platforms...",
+                Style::default().fg(Color::Yellow),
+            )));
+        } else if picker.choices.is_empty() && picker.input.contains("//") {
+            items.push(ListItem::new(Span::styled(
+                format!("Enter: use {}", picker.input.trim()),
+                Style::default().fg(Color::Gray),
+            )));
+        }
+
+        self.platform_list_state.select(Some(picker.selected));
+        let list = List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("↑/↓: select, Enter: apply, Esc: cancel"),
+        );
+        f.render_stateful_widget(list, chunks[1], &mut self.platform_list_state);
+    }
 }